@@ -2,12 +2,19 @@
 
  */
 
-use crate::tree::GedcomData;
-use anyhow::{Result, anyhow};
-use std::collections::{HashMap, HashSet, BTreeSet};
-use serde_json::{to_string, to_string_pretty};
-
-type Xref = String;
+use crate::duplicates::{find_duplicates_in, DuplicateCandidate, DuplicateOptions};
+use crate::implex::{implex, ImplexReport};
+use crate::kinship::{inbreeding_coefficient, relationship_coefficient, Coefficient, KinshipOptions};
+use crate::names::{name_frequencies, NameFrequencies};
+use crate::numbering::NumberingOptions;
+use crate::relationship::{relationship, Relationship};
+use crate::tree::{GedcomData, TreeError};
+use crate::types::{Pedigree, Xref};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet, BTreeSet, VecDeque};
+use serde::Serialize;
+use serde_json::to_string;
+use std::fmt;
 
 /// Analyzer implements all functions to create json data from `GedcomData`
 pub struct Analyzer<'b> {
@@ -17,6 +24,11 @@ pub struct Analyzer<'b> {
     pub components: Vec<HashSet<&'b Xref>>,
     /// topologically sorted individuals of the `GedcomData`
     pub individuals_sorted: Vec<&'b Xref>,
+    /// links followed by the graph traversals
+    pub options: GraphOptions,
+    /// parent-to-child links left out of `individuals_sorted` to break
+    /// cycles, with `GraphOptions::break_cycles`
+    pub excluded_links: Vec<CycleLink>,
 }
 
 impl<'b> Analyzer<'b> {
@@ -29,20 +41,110 @@ impl<'b> Analyzer<'b> {
     ///
     /// # Errors
     ///
-    /// * Tree has Cycle
+    /// * `TreeError::Cycle` naming the individuals and families of a cycle
     ///
     pub fn new(tree: &'b GedcomData) -> Result<Self> {
+        Self::with_options(tree, GraphOptions::default())
+    }
 
-        let individuals_sorted = topological_sort(tree)?;
-        let components = connected_components(tree);
+    /// create a new `Analyzer` whose connected components also follow the
+    /// association and alias links selected in `options`, and which may
+    /// break the cycles of the tree
+    ///
+    /// # Arguments
+    ///
+    /// * tree - reference to a `GedcomData` type
+    /// * options - the additional links to follow
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::Cycle` naming the individuals and families of a cycle,
+    ///   unless `options.break_cycles`
+    ///
+    pub fn with_options(tree: &'b GedcomData, options: GraphOptions) -> Result<Self> {
+
+        let (individuals_sorted, excluded_links) = if options.break_cycles {
+            topological_sort_breaking_cycles(tree)
+        } else {
+            (topological_sort(tree)?, Vec::new())
+        };
+        let components = connected_components_with(tree, options);
         
         Ok( Self {
             tree,
             components,
             individuals_sorted,
+            options,
+            excluded_links,
         })
     }
 
+    /// pairs of individuals that may be the same person, reusing the
+    /// analyzer's connected components. See `duplicates::find_duplicates`.
+    #[must_use]
+    pub fn find_duplicates(&self, options: DuplicateOptions) -> Vec<DuplicateCandidate> {
+        find_duplicates_in(self.tree, &self.components, options)
+    }
+
+    /// individuals directly linked to `xref`, following the links selected
+    /// in the analyzer's options
+    #[must_use]
+    pub fn linked_individuals(&self, xref: &Xref) -> Vec<(&'b Xref, EdgeType)> {
+        linked_individuals(self.tree, xref, self.options)
+    }
+
+    /// ancestors of `xref`, see `analyzer::ancestors`
+    #[must_use]
+    pub fn ancestors(&self, xref: &Xref, options: TraversalOptions) -> Lineage<'b> {
+        ancestors(self.tree, xref, options)
+    }
+
+    /// descendants of `xref`, see `analyzer::descendants`
+    #[must_use]
+    pub fn descendants(&self, xref: &Xref, options: TraversalOptions) -> Lineage<'b> {
+        descendants(self.tree, xref, options)
+    }
+
+    /// what `other` is to `xref` in words, ie. `2nd cousin once removed`.
+    /// See `relationship::relationship`.
+    #[must_use]
+    pub fn relationship(&self, xref: &Xref, other: &Xref) -> Option<Relationship> {
+        relationship(self.tree, xref, other)
+    }
+
+    /// pedigree collapse of the ancestry of `xref`, see `implex::implex`
+    #[must_use]
+    pub fn implex(&self, xref: &Xref, options: NumberingOptions) -> Option<ImplexReport> {
+        implex(self.tree, xref, options)
+    }
+
+    /// Wright's coefficient of relationship between `xref` and `other`, see
+    /// `kinship::relationship_coefficient`
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::NotFound` if either individual doesn't exist
+    /// * `TreeError::Cycle` if the ancestry of either has a cycle
+    ///
+    pub fn relationship_coefficient(&self,
+                                    xref: &Xref,
+                                    other: &Xref,
+                                    options: KinshipOptions) -> std::result::Result<Coefficient, TreeError> {
+        relationship_coefficient(self.tree, xref, other, options)
+    }
+
+    /// Wright's inbreeding coefficient of `xref`, see
+    /// `kinship::inbreeding_coefficient`
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::NotFound` if `xref` isn't an individual
+    /// * `TreeError::Cycle` if the ancestry of `xref` has a cycle
+    ///
+    pub fn inbreeding_coefficient(&self, xref: &Xref, options: KinshipOptions) -> std::result::Result<Coefficient, TreeError> {
+        inbreeding_coefficient(self.tree, xref, options)
+    }
+
     /// return individual data in a json strong
     ///
     /// # Errors
@@ -52,13 +154,14 @@ impl<'b> Analyzer<'b> {
         to_string(&self.tree.individuals)
     }
 
-    /// return name counts in a json string
+    /// return name counts in a json string, counting whole `NAME` values.
+    /// See `name_frequencies` for surnames and given names.
     ///
     /// # Errors
     /// * `serde_json::Error`
     ///
     pub fn count_individual_names(&self) -> std::result::Result<String, serde_json::Error> {
-        let mut counter = HashMap::new();
+        let mut counter = BTreeMap::new();
 
         for &xref in &self.individuals_sorted {
 
@@ -78,15 +181,133 @@ impl<'b> Analyzer<'b> {
         
     }
 
+    /// frequencies of the surnames and given names, see
+    /// `names::name_frequencies`
+    #[must_use]
+    pub fn name_frequencies(&self) -> NameFrequencies {
+        name_frequencies(self.tree)
+    }
+
     
     
 }
 
+/// Kind of link between two individuals in the family graph
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeType {
+    /// from a parent to one of their children
+    Child,
+    /// from a child to one of their parents
+    Parent,
+    /// between individuals linked by an `ASSO` association, ie. a godparent
+    Association,
+    /// between records of the same person linked by `ALIA`
+    Alias,
+}
+
+/// Options selecting which links the graph traversals follow in addition to
+/// parent and child links
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GraphOptions {
+    /// follow `ASSO` associations between individuals
+    pub include_associations: bool,
+    /// follow `ALIA` links between records of the same person
+    pub include_aliases: bool,
+    /// leave out the parent-to-child links closing a cycle when sorting the
+    /// individuals, instead of failing. See `topological_sort_breaking_cycles`.
+    pub break_cycles: bool,
+}
+
+/// Adjacency of individuals in the family graph
+struct Links<'c> {
+    tree: &'c GedcomData,
+    options: GraphOptions,
+    /// associations and aliases pointing at an individual, so they can be walked backwards
+    incoming: HashMap<&'c Xref, Vec<(&'c Xref, EdgeType)>>,
+}
+
+impl<'c> Links<'c> {
+    fn new(tree: &'c GedcomData, options: GraphOptions) -> Self {
+        let mut incoming: HashMap<&'c Xref, Vec<(&'c Xref, EdgeType)>> = HashMap::new();
+
+        for (xref, indv) in &tree.individuals {
+            if options.include_associations {
+                for association in &indv.associations {
+                    incoming
+                        .entry(&association.xref)
+                        .or_default()
+                        .push((xref, EdgeType::Association));
+                }
+            }
+            if options.include_aliases {
+                for alias in &indv.aliases {
+                    incoming.entry(alias).or_default().push((xref, EdgeType::Alias));
+                }
+            }
+        }
+
+        Self { tree, options, incoming }
+    }
+
+    /// individuals linked to `xref`, along with the type of each link
+    fn of(&self, xref: &Xref) -> Vec<(&'c Xref, EdgeType)> {
+        let mut links = Vec::new();
+
+        if let Some(indv) = self.tree.individuals.get(xref) {
+            for xref_fam_sp in &indv.fam_spouse {
+                if let Some(fam_sp) = self.tree.families.get(xref_fam_sp) {
+                    for xref_chld in &fam_sp.children {
+                        links.push((xref_chld, EdgeType::Child));
+                    }
+                }
+            }
+            for xref_fam_chld in indv.fam_child.keys() {
+                if let Some(fam_chld) = self.tree.families.get(xref_fam_chld) {
+                    for xref_parent in fam_chld.husbs.iter().chain(&fam_chld.wives) {
+                        links.push((xref_parent, EdgeType::Parent));
+                    }
+                }
+            }
+            if self.options.include_associations {
+                for association in &indv.associations {
+                    links.push((&association.xref, EdgeType::Association));
+                }
+            }
+            if self.options.include_aliases {
+                for alias in &indv.aliases {
+                    links.push((alias, EdgeType::Alias));
+                }
+            }
+        }
+
+        if let Some(incoming) = self.incoming.get(xref) {
+            links.extend(incoming.iter().copied());
+        }
+
+        links
+    }
+}
+
+/// Get the individuals directly linked to `xref`, along with the type of each link
+#[must_use]
+pub fn linked_individuals<'c>(tree: &'c GedcomData,
+                              xref: &Xref,
+                              options: GraphOptions) -> Vec<(&'c Xref, EdgeType)> {
+    Links::new(tree, options).of(xref)
+}
+
 /// Get connected components of the `GedcomData`
 #[must_use]
-pub fn connected_components<'c>(tree: &'c GedcomData) -> Vec<HashSet<&'c Xref>> {
-    
-    fn bfs<'c>(tree: &'c GedcomData,
+pub fn connected_components(tree: &GedcomData) -> Vec<HashSet<&Xref>> {
+    connected_components_with(tree, GraphOptions::default())
+}
+
+/// Get connected components of the `GedcomData`, following the links selected by `options`
+#[must_use]
+pub fn connected_components_with(tree: &GedcomData,
+                                 options: GraphOptions) -> Vec<HashSet<&Xref>> {
+
+    fn bfs<'c>(links: &Links<'c>,
                unvisited: &mut BTreeSet<&'c Xref>,
                xref: &'c Xref) -> HashSet<&'c Xref> {
 
@@ -94,36 +315,10 @@ pub fn connected_components<'c>(tree: &'c GedcomData) -> Vec<HashSet<&'c Xref>>
         let mut visited: HashSet<&'c Xref> = HashSet::from([xref]);
         
         while let Some(current_xref) = stack.pop() {
-            if let Some(indv) = tree.individuals.get(current_xref) {
-                for xref_fam_sp in &indv.fam_spouse {
-                    if let Some(fam_sp) = tree.families.get(xref_fam_sp) {
-                        
-                        for xref_chld in &fam_sp.children {
-                            if unvisited.remove(xref_chld) {
-                                stack.push(xref_chld);
-                                visited.insert(xref_chld);
-                            }
-                            
-                        }
-                    }
-                }
-                for xref_fam_chld in indv.fam_child.keys() {
-                    if let Some(fam_chld) = tree.families.get(xref_fam_chld) {
-                        
-                        for xref_parent in &fam_chld.husbs {
-                            if unvisited.remove(xref_parent) {
-                                stack.push(xref_parent);
-                                visited.insert(xref_parent);
-                            }
-                        }
-
-                        for xref_parent in &fam_chld.wives {
-                            if unvisited.remove(xref_parent) {
-                                stack.push(xref_parent);
-                                visited.insert(xref_parent);
-                            }
-                        }
-                    }
+            for (xref_linked, _) in links.of(current_xref) {
+                if unvisited.remove(xref_linked) {
+                    stack.push(xref_linked);
+                    visited.insert(xref_linked);
                 }
             }
         }
@@ -131,17 +326,52 @@ pub fn connected_components<'c>(tree: &'c GedcomData) -> Vec<HashSet<&'c Xref>>
         visited
     }
 
-    let mut unvisited: BTreeSet<&'c Xref> = tree.individuals.keys().collect();
+    let links = Links::new(tree, options);
+    let mut unvisited: BTreeSet<&Xref> = tree.individuals.keys().collect();
     let mut components = Vec::new();
 
     while let Some(xref) = unvisited.pop_first() {
-        components.push(bfs(tree, &mut unvisited, xref));
+        components.push(bfs(&links, &mut unvisited, xref));
     }
 
     components
         
 }
 
+/// A parent-to-child link, through the family the child belongs to
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct CycleLink {
+    /// the individual recorded as a parent in the family
+    pub parent: Xref,
+    /// the family
+    pub family: Xref,
+    /// the individual recorded as a child of the family
+    pub child: Xref,
+}
+
+/// Parent-to-child links leading from an individual back to itself, ie. an
+/// individual recorded as their own ancestor
+///
+/// The child of each link is the parent of the next one, and the child of the
+/// last link is the parent of the first one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Cycle {
+    /// the links forming the cycle
+    pub links: Vec<CycleLink>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(first) = self.links.first() {
+            write!(f, "{}", first.parent)?;
+        }
+        for link in &self.links {
+            write!(f, " -> {} -> {}", link.family, link.child)?;
+        }
+        Ok(())
+    }
+}
+
 /// Sort the individuals in topological order
 ///
 /// # Arguments
@@ -150,27 +380,85 @@ pub fn connected_components<'c>(tree: &'c GedcomData) -> Vec<HashSet<&'c Xref>>
 ///
 /// # Errors
 ///
-/// * Tree has Cycle
+/// * `TreeError::Cycle` naming the individuals and families of a cycle
+///
+pub fn topological_sort(tree: &GedcomData) -> std::result::Result<Vec<&Xref>, TreeError> {
+    sort_excluding(tree, &HashSet::new()).map_err(|links| TreeError::Cycle(cycle(&links)))
+}
+
+/// Sort the individuals in topological order, leaving out the
+/// parent-to-child links that close a cycle
+///
+/// Returns the sorted individuals and the links left out, in the order the
+/// cycles were found. Without cycles no link is left out and the order is
+/// the one of `topological_sort`.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
 ///
-pub fn topological_sort<'c>(tree: &'c GedcomData) -> Result<Vec<&'c Xref>> {
+#[must_use]
+pub fn topological_sort_breaking_cycles(tree: &GedcomData) -> (Vec<&Xref>, Vec<CycleLink>) {
+    let mut excluded = HashSet::new();
+    let mut links = Vec::new();
+    loop {
+        match sort_excluding(tree, &excluded) {
+            Ok(sorted) => return (sorted, links),
+            Err(cycle_links) => {
+                // the link found last leads back to the start of the cycle
+                let closing = cycle_links[cycle_links.len() - 1];
+                excluded.insert(closing);
+                links.extend(cycle(&[closing]).links);
+            }
+        }
+    }
+}
+
+/// A `(parent, family, child)` link of the family graph
+type Link<'c> = (&'c Xref, &'c Xref, &'c Xref);
+
+fn cycle(links: &[Link]) -> Cycle {
+    Cycle {
+        links: links
+            .iter()
+            .map(|(parent, family, child)| CycleLink {
+                parent: (*parent).clone(),
+                family: (*family).clone(),
+                child: (*child).clone(),
+            })
+            .collect(),
+    }
+}
+
+/// `topological_sort` without the links in `excluded`, failing with the
+/// links of the first cycle found
+fn sort_excluding<'c>(tree: &'c GedcomData,
+                      excluded: &HashSet<Link<'c>>) -> std::result::Result<Vec<&'c Xref>, Vec<Link<'c>>> {
 
     enum Mark {
         Perm,
         Temp,
     }
 
+    /// `path` holds the links from the individual the visit started at down
+    /// to `xref`
     fn visit<'c>(tree: &'c GedcomData,
+                 excluded: &HashSet<Link<'c>>,
                  to_visit: &mut BTreeSet<&'c Xref>,
                  marks: &mut HashMap<&'c Xref, Mark>,
                  sorted: &mut Vec<&'c Xref>,
-                 xref: &'c Xref) -> Result<()> {
+                 path: &mut Vec<Link<'c>>,
+                 xref: &'c Xref) -> std::result::Result<(), Vec<Link<'c>>> {
 
         to_visit.remove(xref);
         
         if let Some(mark) = marks.get(xref) {
             match mark {
                 Mark::Perm => return Ok(()),
-                Mark::Temp => return Err(anyhow!("tree has a cycle")),
+                Mark::Temp => {
+                    let start = path.iter().position(|(parent, _, _)| *parent == xref).unwrap_or(0);
+                    return Err(path[start..].to_vec());
+                }
             }
         }
 
@@ -178,9 +466,14 @@ pub fn topological_sort<'c>(tree: &'c GedcomData) -> Result<Vec<&'c Xref>> {
 
         if let Some(indv) = tree.individuals.get(xref) {
             for xref_fam in &indv.fam_spouse {
-                if let Some(fam) = tree.families.get(xref_fam) {
+                if let Some((xref_fam, fam)) = tree.families.get_key_value(xref_fam) {
                     for xref_chld in &fam.children {
-                        visit(tree, to_visit, marks, sorted, xref_chld)?;
+                        if excluded.contains(&(xref, xref_fam, xref_chld)) {
+                            continue;
+                        }
+                        path.push((xref, xref_fam, xref_chld));
+                        visit(tree, excluded, to_visit, marks, sorted, path, xref_chld)?;
+                        path.pop();
                     }
                 }
             }
@@ -195,9 +488,10 @@ pub fn topological_sort<'c>(tree: &'c GedcomData) -> Result<Vec<&'c Xref>> {
     let mut to_visit: BTreeSet<&Xref> = tree.individuals.keys().collect();
     let mut marks: HashMap<&'c Xref, Mark> = HashMap::new();
     let mut sorted: Vec<&'c Xref> = Vec::new();
+    let mut path = Vec::new();
 
     while let Some(xref) = to_visit.pop_first() {
-        visit(tree, &mut to_visit, &mut marks, &mut sorted, xref)?;
+        visit(tree, excluded, &mut to_visit, &mut marks, &mut sorted, &mut path, xref)?;
     }
 
     Ok(sorted)
@@ -245,3 +539,167 @@ pub fn topological_sort<'c>(tree: &'c GedcomData) -> Result<Vec<&'c Xref>> {
 //         .collect::<Vec<&Xref>>()
 
 // }
+
+/// Order in which `ancestors` and `descendants` visit individuals
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// generation by generation
+    #[default]
+    BreadthFirst,
+    /// each line to its end before the next one
+    DepthFirst,
+}
+
+/// Options of `ancestors` and `descendants`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TraversalOptions {
+    /// order in which individuals are visited
+    pub order: TraversalOrder,
+    /// only follow child-to-family links with a `Birth` pedigree, or no
+    /// pedigree at all
+    pub birth_only: bool,
+    /// stop this many generations away from the start, `None` for no limit
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Direction {
+    Ancestors,
+    Descendants,
+}
+
+/// Lazy iterator over the ancestors or descendants of an individual, see
+/// `ancestors` and `descendants`
+///
+/// Yields `(xref, generation, path)`, where `generation` is 1 for parents or
+/// children, and `path` lists the individuals from the start to `xref`.
+pub struct Lineage<'c> {
+    tree: &'c GedcomData,
+    direction: Direction,
+    options: TraversalOptions,
+    pending: VecDeque<(&'c Xref, usize, Vec<&'c Xref>)>,
+    visited: HashSet<&'c Xref>,
+}
+
+impl<'c> Lineage<'c> {
+    fn new(tree: &'c GedcomData, xref: &Xref, direction: Direction, options: TraversalOptions) -> Self {
+        let mut lineage = Lineage {
+            tree,
+            direction,
+            options,
+            pending: VecDeque::new(),
+            visited: HashSet::new(),
+        };
+        if let Some((start, _)) = tree.individuals.get_key_value(xref) {
+            lineage.visited.insert(start);
+            lineage.push_next(start, 0, &[start]);
+        }
+        lineage
+    }
+
+    /// Queues the unvisited parents or children of `xref`
+    fn push_next(&mut self, xref: &'c Xref, generation: usize, path: &[&'c Xref]) {
+        if self.options.max_depth.is_some_and(|max| generation >= max) {
+            return;
+        }
+        let mut next = Vec::new();
+        if let Some(indv) = self.tree.individuals.get(xref) {
+            match self.direction {
+                Direction::Ancestors => {
+                    for (xref_fam, link) in &indv.fam_child {
+                        if self.options.birth_only && !is_birth(link.pedigree()) {
+                            continue;
+                        }
+                        if let Some(fam) = self.tree.families.get(xref_fam) {
+                            next.extend(fam.husbs.iter().chain(&fam.wives));
+                        }
+                    }
+                }
+                Direction::Descendants => {
+                    for xref_fam in &indv.fam_spouse {
+                        if let Some(fam) = self.tree.families.get(xref_fam) {
+                            next.extend(fam.children.iter().filter(|xref_chld| {
+                                !self.options.birth_only || self.is_born_to(xref_chld, xref_fam)
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut queued = Vec::new();
+        for relative in next {
+            if self.tree.individuals.contains_key(relative) && self.visited.insert(relative) {
+                let mut relative_path = path.to_vec();
+                relative_path.push(relative);
+                queued.push((relative, generation + 1, relative_path));
+            }
+        }
+        match self.options.order {
+            TraversalOrder::BreadthFirst => self.pending.extend(queued),
+            // pushed in reverse so that the first relative is visited first
+            TraversalOrder::DepthFirst => {
+                for entry in queued.into_iter().rev() {
+                    self.pending.push_front(entry);
+                }
+            }
+        }
+    }
+
+    fn is_born_to(&self, xref_chld: &Xref, xref_fam: &Xref) -> bool {
+        self.tree
+            .individuals
+            .get(xref_chld)
+            .and_then(|child| child.fam_child.get(xref_fam))
+            .is_some_and(|link| is_birth(link.pedigree()))
+    }
+}
+
+impl<'c> Iterator for Lineage<'c> {
+    type Item = (&'c Xref, usize, Vec<&'c Xref>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (xref, generation, path) = self.pending.pop_front()?;
+        self.push_next(xref, generation, &path);
+        Some((xref, generation, path))
+    }
+}
+
+/// A missing pedigree means a birth link
+pub(crate) fn is_birth(pedigree: Option<&Pedigree>) -> bool {
+    matches!(pedigree, None | Some(Pedigree::Birth))
+}
+
+/// Iterate lazily over the ancestors of `xref`: parents, grandparents, ...
+///
+/// Each ancestor is yielded once, along with its generation (1 for parents)
+/// and the path from `xref` to it, even when it can be reached through more
+/// than one line. Nothing is yielded if `xref` isn't an individual.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual to start from
+/// * options - order, pedigrees followed and depth limit
+///
+#[must_use]
+pub fn ancestors<'c>(tree: &'c GedcomData, xref: &Xref, options: TraversalOptions) -> Lineage<'c> {
+    Lineage::new(tree, xref, Direction::Ancestors, options)
+}
+
+/// Iterate lazily over the descendants of `xref`: children, grandchildren, ...
+///
+/// Each descendant is yielded once, along with its generation (1 for
+/// children) and the path from `xref` to it. Nothing is yielded if `xref`
+/// isn't an individual.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual to start from
+/// * options - order, pedigrees followed and depth limit
+///
+#[must_use]
+pub fn descendants<'c>(tree: &'c GedcomData, xref: &Xref, options: TraversalOptions) -> Lineage<'c> {
+    Lineage::new(tree, xref, Direction::Descendants, options)
+}
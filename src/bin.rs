@@ -1,7 +1,5 @@
 use gedcom::util::parse;
-use gedcom::GedcomData;
 use std::env;
-use std::fs;
 use anyhow::Result;
 
 fn main() -> Result<()> {
@@ -20,7 +18,7 @@ fn main() -> Result<()> {
 
     let data = parse(filename)?;
     println!("Parsing complete!");
-    data.stats();
+    print!("{}", data.statistics(0));
 
     Ok(())
 }
@@ -0,0 +1,324 @@
+/*! Builder for constructing a `GedcomData` programmatically
+
+```rust
+use gedcom::GedcomData;
+use gedcom::types::Gender;
+
+let tree = GedcomData::builder()
+    .individual("john").name("John /Doe/").sex(Gender::Male).birth("1 JAN 1900", "Boston")
+    .individual("jane").name("Jane /Roe/").sex(Gender::Female)
+    .individual("jim").name("Jim /Doe/")
+    .family("doe").husband("john").wife("jane").child("jim").marriage("5 JUN 1925", "Boston")
+    .build()
+    .unwrap();
+
+assert_eq!(tree.families["@F1@"].children, vec!["@I3@"]);
+assert!(tree.individuals["@I3@"].fam_child.contains_key("@F1@"));
+```
+
+ */
+
+use crate::tree::{GedcomData, TreeError};
+use crate::types::{
+    event::HasEvents, Event, EventType, Family, FamilyLink, FamilyLinkType, Gender, Individual,
+    Name, Xref,
+};
+use std::collections::HashMap;
+
+/// Builds a `GedcomData` record by record
+///
+/// Records are referred to by labels local to the builder, and are given the
+/// first free xref of the form `@I1@` (individuals) or `@F1@` (families).
+/// Links between individuals and families are added on both sides when the
+/// tree is built, so families may refer to individuals labelled after them.
+#[derive(Debug, Default)]
+pub struct GedcomBuilder {
+    tree: GedcomData,
+    labels: HashMap<String, Xref>,
+    links: Vec<(Xref, FamilyRole, String)>,
+    next_individual: usize,
+    next_family: usize,
+    error: Option<TreeError>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum FamilyRole {
+    Husband,
+    Wife,
+    Child,
+}
+
+impl GedcomBuilder {
+    /// Creates a builder for an empty tree
+    #[must_use]
+    pub fn new() -> GedcomBuilder {
+        GedcomBuilder::default()
+    }
+
+    /// Starts a new individual, referred to by `label` in the builder
+    #[must_use]
+    pub fn individual(mut self, label: &str) -> IndividualBuilder {
+        let xref = self.allocate('I', label);
+        self.tree.individuals.insert(xref.clone(), Individual::default());
+        IndividualBuilder { builder: self, xref }
+    }
+
+    /// Starts a new family, referred to by `label` in the builder
+    #[must_use]
+    pub fn family(mut self, label: &str) -> FamilyBuilder {
+        let xref = self.allocate('F', label);
+        self.tree.families.insert(xref.clone(), Family::default());
+        FamilyBuilder { builder: self, xref }
+    }
+
+    /// The xref given to the record labelled `label`
+    #[must_use]
+    pub fn xref(&self, label: &str) -> Option<&Xref> {
+        self.labels.get(label)
+    }
+
+    /// Links the families to their members and returns the tree
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::DuplicateLabel` if a label was given to more than one record
+    /// * `TreeError::UnknownLabel` if a family refers to a label that isn't an individual
+    /// * `TreeError::DuplicateEvent` if a family was given two events of the same type
+    ///
+    pub fn build(mut self) -> Result<GedcomData, TreeError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        for (xref_fam, role, label) in std::mem::take(&mut self.links) {
+            let member = self
+                .labels
+                .get(&label)
+                .filter(|xref| self.tree.individuals.contains_key(*xref))
+                .ok_or(TreeError::UnknownLabel(label))?;
+
+            let fam = &mut self.tree.families[&xref_fam];
+            let indv = &mut self.tree.individuals[member];
+            match role {
+                FamilyRole::Husband => fam.add_husb(member.clone()),
+                FamilyRole::Wife => fam.add_wife(member.clone()),
+                FamilyRole::Child => fam.add_child(member.clone()),
+            }
+            let link_type = match role {
+                FamilyRole::Child => FamilyLinkType::Child,
+                FamilyRole::Husband | FamilyRole::Wife => FamilyLinkType::Spouse,
+            };
+            indv.add_family(xref_fam, FamilyLink::with_type(link_type));
+        }
+
+        Ok(self.tree)
+    }
+
+    /// Finds the first free xref with the given prefix and gives it `label`
+    fn allocate(&mut self, prefix: char, label: &str) -> Xref {
+        let next = match prefix {
+            'I' => &mut self.next_individual,
+            _ => &mut self.next_family,
+        };
+        let xref = loop {
+            *next += 1;
+            let xref = Xref::new(format!("@{prefix}{next}@")).expect("generated xref is valid");
+            if !self.tree.contains_xref(xref.as_str()) {
+                break xref;
+            }
+        };
+
+        if self.labels.insert(label.to_string(), xref.clone()).is_some() {
+            self.fail(TreeError::DuplicateLabel(label.to_string()));
+        }
+        xref
+    }
+
+    fn fail(&mut self, error: TreeError) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+}
+
+/// Adds records to an existing tree. New xrefs skip those already in use.
+impl From<GedcomData> for GedcomBuilder {
+    fn from(tree: GedcomData) -> Self {
+        GedcomBuilder {
+            tree,
+            ..GedcomBuilder::default()
+        }
+    }
+}
+
+/// Sets the facts of an individual started by `GedcomBuilder::individual`
+#[derive(Debug)]
+pub struct IndividualBuilder {
+    builder: GedcomBuilder,
+    xref: Xref,
+}
+
+impl IndividualBuilder {
+    /// Sets the name, ie. `John /Doe/`, with the surname between slashes
+    #[must_use]
+    pub fn name(mut self, name: &str) -> Self {
+        self.record().name = Some(Name::from_value(name));
+        self
+    }
+
+    /// Sets the sex
+    #[must_use]
+    pub fn sex(mut self, sex: Gender) -> Self {
+        self.record().sex = sex;
+        self
+    }
+
+    /// Sets the title, ie. `Duke of Wellington`
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.record().title = Some(title.to_string());
+        self
+    }
+
+    /// Adds a birth event. Empty `date` or `place` are left unset.
+    #[must_use]
+    pub fn birth(self, date: &str, place: &str) -> Self {
+        self.event(EventType::Birth, date, place)
+    }
+
+    /// Adds a death event. Empty `date` or `place` are left unset.
+    #[must_use]
+    pub fn death(self, date: &str, place: &str) -> Self {
+        self.event(EventType::Death, date, place)
+    }
+
+    /// Adds an event. Empty `date` or `place` are left unset.
+    #[must_use]
+    pub fn event(mut self, event_type: EventType, date: &str, place: &str) -> Self {
+        let event = new_event(event_type, date, place);
+        // individuals accept any number of events of each type
+        let _ = self.record().add_event(event);
+        self
+    }
+
+    /// The xref given to the individual
+    #[must_use]
+    pub fn xref(&self) -> &Xref {
+        &self.xref
+    }
+
+    /// Starts the next individual
+    #[must_use]
+    pub fn individual(self, label: &str) -> IndividualBuilder {
+        self.builder.individual(label)
+    }
+
+    /// Starts the next family
+    #[must_use]
+    pub fn family(self, label: &str) -> FamilyBuilder {
+        self.builder.family(label)
+    }
+
+    /// See `GedcomBuilder::build`
+    ///
+    /// # Errors
+    ///
+    /// * see `GedcomBuilder::build`
+    ///
+    pub fn build(self) -> Result<GedcomData, TreeError> {
+        self.builder.build()
+    }
+
+    fn record(&mut self) -> &mut Individual {
+        &mut self.builder.tree.individuals[&self.xref]
+    }
+}
+
+/// Sets the members and facts of a family started by `GedcomBuilder::family`
+#[derive(Debug)]
+pub struct FamilyBuilder {
+    builder: GedcomBuilder,
+    xref: Xref,
+}
+
+impl FamilyBuilder {
+    /// Adds the individual labelled `label` as a husband (`HUSB`)
+    #[must_use]
+    pub fn husband(self, label: &str) -> Self {
+        self.member(FamilyRole::Husband, label)
+    }
+
+    /// Adds the individual labelled `label` as a wife (`WIFE`)
+    #[must_use]
+    pub fn wife(self, label: &str) -> Self {
+        self.member(FamilyRole::Wife, label)
+    }
+
+    /// Adds the individual labelled `label` as a child (`CHIL`)
+    #[must_use]
+    pub fn child(self, label: &str) -> Self {
+        self.member(FamilyRole::Child, label)
+    }
+
+    /// Adds a marriage event. Empty `date` or `place` are left unset.
+    #[must_use]
+    pub fn marriage(self, date: &str, place: &str) -> Self {
+        self.event(EventType::Marriage, date, place)
+    }
+
+    /// Adds an event. Empty `date` or `place` are left unset. A family can
+    /// only have one event of each type, a second one fails the build.
+    #[must_use]
+    pub fn event(mut self, event_type: EventType, date: &str, place: &str) -> Self {
+        let event = new_event(event_type.clone(), date, place);
+        let fam = &mut self.builder.tree.families[&self.xref];
+        if fam.add_event(event).is_err() {
+            let error = TreeError::DuplicateEvent {
+                xref: self.xref.clone(),
+                event: event_type,
+            };
+            self.builder.fail(error);
+        }
+        self
+    }
+
+    /// The xref given to the family
+    #[must_use]
+    pub fn xref(&self) -> &Xref {
+        &self.xref
+    }
+
+    /// Starts the next individual
+    #[must_use]
+    pub fn individual(self, label: &str) -> IndividualBuilder {
+        self.builder.individual(label)
+    }
+
+    /// Starts the next family
+    #[must_use]
+    pub fn family(self, label: &str) -> FamilyBuilder {
+        self.builder.family(label)
+    }
+
+    /// See `GedcomBuilder::build`
+    ///
+    /// # Errors
+    ///
+    /// * see `GedcomBuilder::build`
+    ///
+    pub fn build(self) -> Result<GedcomData, TreeError> {
+        self.builder.build()
+    }
+
+    fn member(mut self, role: FamilyRole, label: &str) -> Self {
+        self.builder.links.push((self.xref.clone(), role, label.to_string()));
+        self
+    }
+}
+
+fn new_event(event_type: EventType, date: &str, place: &str) -> Event {
+    let mut event = Event::new(event_type);
+    event.date = Some(date.to_string()).filter(|date| !date.is_empty());
+    event.place = Some(place.to_string()).filter(|place| !place.is_empty());
+    event
+}
@@ -0,0 +1,431 @@
+/*! Chronological consistency checks for the `GedcomData` type
+
+ */
+
+use crate::tree::GedcomData;
+use crate::types::{event::HasEvents, DateValue, Event, EventType, Family, Individual, Xref};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Options of `check_chronology`
+#[derive(Clone, Copy, Debug)]
+pub struct ChronologyOptions {
+    /// age of a mother past which the birth of a child is flagged
+    pub max_mother_age: i32,
+    /// age under which a marriage is flagged
+    pub min_marriage_age: i32,
+    /// lifespan in years past which an individual is flagged
+    pub max_lifespan: i32,
+    /// difference in years between the births of spouses past which a
+    /// family is flagged
+    pub max_spouse_age_gap: i32,
+}
+
+impl Default for ChronologyOptions {
+    fn default() -> Self {
+        ChronologyOptions {
+            max_mother_age: 55,
+            min_marriage_age: 12,
+            max_lifespan: 120,
+            max_spouse_age_gap: 30,
+        }
+    }
+}
+
+/// How serious a `ChronologyIssue` is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Severity {
+    /// unlikely, but possible
+    Warning,
+    /// impossible, so at least one of the dates is wrong
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// Kind of problem found by `check_chronology`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum ChronologyKind {
+    /// The individual was born after their death
+    BirthAfterDeath,
+    /// The individual was buried before their death
+    BurialBeforeDeath,
+    /// An event of the record is dated after the death of `deceased`, the
+    /// individual itself or a spouse of the family
+    EventAfterDeath {
+        /// the type of the event
+        event: EventType,
+        /// the individual who had died
+        deceased: Xref,
+    },
+    /// The individual lived longer than `ChronologyOptions::max_lifespan`
+    LongLifespan {
+        /// the age at death, in full years
+        years: i32,
+    },
+    /// A child of the family was born before one of its parents
+    BornBeforeParent {
+        /// the child
+        child: Xref,
+        /// the parent
+        parent: Xref,
+    },
+    /// A child of the family was born when its mother was older than
+    /// `ChronologyOptions::max_mother_age`
+    BornAfterMotherAge {
+        /// the child
+        child: Xref,
+        /// the mother
+        mother: Xref,
+        /// the age of the mother, in full years
+        age: i32,
+    },
+    /// A child of the family was born more than nine months after the death
+    /// of its father
+    BornAfterFatherDeath {
+        /// the child
+        child: Xref,
+        /// the father
+        father: Xref,
+    },
+    /// The family's marriage is dated before the birth of `spouse`
+    MarriageBeforeBirth {
+        /// the spouse
+        spouse: Xref,
+    },
+    /// A spouse of the family married younger than
+    /// `ChronologyOptions::min_marriage_age`
+    EarlyMarriage {
+        /// the spouse
+        spouse: Xref,
+        /// the age of the spouse at the marriage, in full years
+        age: i32,
+    },
+    /// The spouses of the family were born further apart than
+    /// `ChronologyOptions::max_spouse_age_gap`
+    SpouseAgeGap {
+        /// the difference between the births, in full years
+        years: i32,
+    },
+}
+
+/// A chronological problem, located at the record it was found in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChronologyIssue {
+    /// xref of the record containing the problem
+    pub xref: Xref,
+    /// tag of the record type, ie. `INDI`
+    pub record_type: &'static str,
+    /// line of the parsed file on which the record starts, if known
+    pub line: Option<u32>,
+    /// how serious the problem is
+    pub severity: Severity,
+    /// what is wrong
+    pub kind: ChronologyKind,
+}
+
+impl fmt::Display for ChronologyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        write!(f, "{} {}: {}: ", self.xref, self.record_type, self.severity)?;
+        match &self.kind {
+            ChronologyKind::BirthAfterDeath => f.write_str("born after death"),
+            ChronologyKind::BurialBeforeDeath => f.write_str("buried before death"),
+            ChronologyKind::EventAfterDeath { event, deceased } => {
+                write!(f, "{event} event after the death of {deceased}")
+            }
+            ChronologyKind::LongLifespan { years } => write!(f, "lived {years} years"),
+            ChronologyKind::BornBeforeParent { child, parent } => {
+                write!(f, "child {child} born before parent {parent}")
+            }
+            ChronologyKind::BornAfterMotherAge { child, mother, age } => {
+                write!(f, "child {child} born when mother {mother} was {age}")
+            }
+            ChronologyKind::BornAfterFatherDeath { child, father } => {
+                write!(f, "child {child} born more than 9 months after the death of father {father}")
+            }
+            ChronologyKind::MarriageBeforeBirth { spouse } => write!(f, "married before the birth of {spouse}"),
+            ChronologyKind::EarlyMarriage { spouse, age } => write!(f, "{spouse} married at {age}"),
+            ChronologyKind::SpouseAgeGap { years } => write!(f, "spouses born {years} years apart"),
+        }
+    }
+}
+
+/// Check that the dates of the `GedcomData` are possible and plausible
+///
+/// Individuals are checked for births after death, burials before death,
+/// events after death and long lifespans. Families are checked for children
+/// born before a parent, after the mother's `max_mother_age` or more than nine
+/// months after the father's death, marriages before a spouse's birth or
+/// `min_marriage_age`, events after the death of a spouse, and spouses born
+/// far apart.
+///
+/// Only exact dates are compared: approximate dates, ranges and periods are
+/// skipped, and a date missing its month or day is only flagged if every day
+/// it could stand for is. Issues are listed in record order, individuals
+/// first.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * options - thresholds of the plausibility checks
+///
+#[must_use]
+pub fn check_chronology(tree: &GedcomData, options: ChronologyOptions) -> Vec<ChronologyIssue> {
+    let mut checker = Checker {
+        tree,
+        options,
+        lifetimes: tree
+            .individuals
+            .iter()
+            .map(|(xref, indv)| (xref, Lifetime::new(indv)))
+            .collect(),
+        issues: Vec::new(),
+    };
+
+    for (xref, indv) in &tree.individuals {
+        checker.check_individual(xref, indv);
+    }
+    for (xref, fam) in &tree.families {
+        checker.check_family(xref, fam);
+    }
+
+    checker.issues
+}
+
+/// Earliest and latest day an exact date can stand for, as
+/// `(year, month, day)`
+#[derive(Clone, Copy, Debug)]
+struct Span {
+    earliest: (i32, u8, u8),
+    latest: (i32, u8, u8),
+}
+
+impl Span {
+    fn of(event: &Event) -> Option<Span> {
+        let date = DateValue::parse(event.date.as_deref()?)?;
+        if date.approximate {
+            return None;
+        }
+        Some(Span {
+            earliest: (date.year, date.month.unwrap_or(1), date.day.unwrap_or(1)),
+            latest: (date.year, date.month.unwrap_or(12), date.day.unwrap_or(31)),
+        })
+    }
+
+    fn is_before(&self, other: &Span) -> bool {
+        self.latest < other.earliest
+    }
+
+    /// Fewest full years from `self` to `other`
+    fn min_years_to(&self, other: &Span) -> i32 {
+        years_between(self.latest, other.earliest)
+    }
+
+    /// Most full years from `self` to `other`
+    fn max_years_to(&self, other: &Span) -> i32 {
+        years_between(self.earliest, other.latest)
+    }
+}
+
+fn years_between(from: (i32, u8, u8), to: (i32, u8, u8)) -> i32 {
+    let years = to.0 - from.0;
+    if (to.1, to.2) < (from.1, from.2) {
+        years - 1
+    } else {
+        years
+    }
+}
+
+/// Dates of the first birth, death and burial events of an individual
+struct Lifetime {
+    birth: Option<Span>,
+    death: Option<Span>,
+    burial: Option<Span>,
+}
+
+impl Lifetime {
+    fn new(indv: &Individual) -> Lifetime {
+        let events = indv.events();
+        let span = |event_type: EventType| {
+            events.iter().find(|event| event.event == event_type).and_then(Span::of)
+        };
+        Lifetime {
+            birth: span(EventType::Birth),
+            death: span(EventType::Death),
+            burial: span(EventType::Burial),
+        }
+    }
+}
+
+struct Checker<'a> {
+    tree: &'a GedcomData,
+    options: ChronologyOptions,
+    lifetimes: HashMap<&'a Xref, Lifetime>,
+    issues: Vec<ChronologyIssue>,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, xref: &Xref, record_type: &'static str, severity: Severity, kind: ChronologyKind) {
+        self.issues.push(ChronologyIssue {
+            xref: xref.clone(),
+            record_type,
+            line: self.tree.record_lines(xref.as_str()).first().copied(),
+            severity,
+            kind,
+        });
+    }
+
+    fn lifetime(&self, xref: &Xref) -> Option<&Lifetime> {
+        self.lifetimes.get(xref)
+    }
+
+    fn check_individual(&mut self, xref: &Xref, indv: &Individual) {
+        let Some(&Lifetime { birth, death, burial }) = self.lifetime(xref) else {
+            return;
+        };
+
+        if let (Some(birth), Some(death)) = (birth, death) {
+            if death.is_before(&birth) {
+                self.push(xref, "INDI", Severity::Error, ChronologyKind::BirthAfterDeath);
+            } else if birth.min_years_to(&death) > self.options.max_lifespan {
+                let years = birth.min_years_to(&death);
+                self.push(xref, "INDI", Severity::Warning, ChronologyKind::LongLifespan { years });
+            }
+        }
+        if let Some(death) = death {
+            if burial.is_some_and(|burial| burial.is_before(&death)) {
+                self.push(xref, "INDI", Severity::Error, ChronologyKind::BurialBeforeDeath);
+            }
+            self.check_events_after_death(xref, "INDI", &indv.events(), xref, &death);
+        }
+    }
+
+    /// Flags the `events` of the record dated after `death`, except the
+    /// burial
+    fn check_events_after_death(&mut self,
+                                xref: &Xref,
+                                record_type: &'static str,
+                                events: &[Event],
+                                deceased: &Xref,
+                                death: &Span) {
+        for event in events {
+            if matches!(event.event, EventType::Birth | EventType::Death | EventType::Burial) {
+                continue;
+            }
+            if Span::of(event).is_some_and(|span| death.is_before(&span)) {
+                let kind = ChronologyKind::EventAfterDeath {
+                    event: event.event.clone(),
+                    deceased: deceased.clone(),
+                };
+                self.push(xref, record_type, Severity::Error, kind);
+            }
+        }
+    }
+
+    fn check_family(&mut self, xref: &Xref, fam: &Family) {
+        for xref_child in &fam.children {
+            if let Some(birth) = self.lifetime(xref_child).and_then(|lifetime| lifetime.birth) {
+                self.check_child(xref, fam, xref_child, &birth);
+            }
+        }
+        self.check_spouses(xref, fam);
+    }
+
+    fn check_child(&mut self, xref: &Xref, fam: &Family, xref_child: &Xref, child_birth: &Span) {
+        let parents = fam.husbs.iter().map(|parent| (parent, true));
+        for (xref_parent, is_father) in parents.chain(fam.wives.iter().map(|parent| (parent, false))) {
+            let Some(&Lifetime { birth, death, .. }) = self.lifetime(xref_parent) else {
+                continue;
+            };
+            if let Some(birth) = birth {
+                let age = birth.min_years_to(child_birth);
+                if child_birth.is_before(&birth) {
+                    let kind = ChronologyKind::BornBeforeParent {
+                        child: xref_child.clone(),
+                        parent: xref_parent.clone(),
+                    };
+                    self.push(xref, "FAM", Severity::Error, kind);
+                } else if !is_father && age > self.options.max_mother_age {
+                    let kind = ChronologyKind::BornAfterMotherAge {
+                        child: xref_child.clone(),
+                        mother: xref_parent.clone(),
+                        age,
+                    };
+                    self.push(xref, "FAM", Severity::Warning, kind);
+                }
+            }
+            let death = death.filter(|_| is_father);
+            if death.is_some_and(|death| nine_months_after(death.latest) < child_birth.earliest) {
+                let kind = ChronologyKind::BornAfterFatherDeath {
+                    child: xref_child.clone(),
+                    father: xref_parent.clone(),
+                };
+                self.push(xref, "FAM", Severity::Error, kind);
+            }
+        }
+    }
+
+    fn check_spouses(&mut self, xref: &Xref, fam: &Family) {
+        let events = fam.events();
+        let marriage = events
+            .iter()
+            .find(|event| event.event == EventType::Marriage)
+            .and_then(Span::of);
+        let spouses: Vec<&Xref> = fam.husbs.iter().chain(&fam.wives).collect();
+
+        for xref_spouse in &spouses {
+            let Some(&Lifetime { birth, death, .. }) = self.lifetime(xref_spouse) else {
+                continue;
+            };
+            if let (Some(birth), Some(marriage)) = (birth, marriage) {
+                let age = birth.max_years_to(&marriage);
+                if marriage.is_before(&birth) {
+                    let kind = ChronologyKind::MarriageBeforeBirth {
+                        spouse: (*xref_spouse).clone(),
+                    };
+                    self.push(xref, "FAM", Severity::Error, kind);
+                } else if age < self.options.min_marriage_age {
+                    let kind = ChronologyKind::EarlyMarriage {
+                        spouse: (*xref_spouse).clone(),
+                        age,
+                    };
+                    self.push(xref, "FAM", Severity::Warning, kind);
+                }
+            }
+            if let Some(death) = death {
+                self.check_events_after_death(xref, "FAM", &events, xref_spouse, &death);
+            }
+        }
+
+        let births: Vec<Span> = spouses
+            .iter()
+            .filter_map(|xref_spouse| self.lifetime(xref_spouse).and_then(|lifetime| lifetime.birth))
+            .collect();
+        if let [first, second] = births.as_slice() {
+            let (older, younger) = if first.is_before(second) { (first, second) } else { (second, first) };
+            let years = older.min_years_to(younger);
+            if years > self.options.max_spouse_age_gap {
+                self.push(xref, "FAM", Severity::Warning, ChronologyKind::SpouseAgeGap { years });
+            }
+        }
+    }
+}
+
+/// The day nine months after `date`, past which a child can't be born to a
+/// father who died on `date`
+fn nine_months_after((year, month, day): (i32, u8, u8)) -> (i32, u8, u8) {
+    let months = i32::from(month) - 1 + 9;
+    (year + months / 12, u8::try_from(months % 12 + 1).unwrap_or(12), day)
+}
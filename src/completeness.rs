@@ -0,0 +1,245 @@
+/*! Data completeness and research gaps of the `GedcomData` type
+
+ */
+
+use crate::tree::GedcomData;
+use crate::types::{event::HasEvents, Event, EventType, Family, Individual, Xref};
+use serde::Serialize;
+use std::fmt;
+
+/// Something missing from a record
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum Gap {
+    /// no birth event of the individual has a date
+    BirthDate,
+    /// no birth event of the individual has a place
+    BirthPlace,
+    /// no death event of the individual has a date. Living individuals are
+    /// counted too.
+    DeathDate,
+    /// no death event of the individual has a place. Living individuals are
+    /// counted too.
+    DeathPlace,
+    /// the individual is no child of any family, a "brick wall"
+    Parents,
+    /// the family has no husband or wife, so the parents of its children
+    /// are unknown
+    Spouses,
+    /// the family has no marriage event
+    Marriage,
+    /// an event of the type has no source citation
+    Source(EventType),
+}
+
+impl fmt::Display for Gap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gap::BirthDate => f.write_str("no birth date"),
+            Gap::BirthPlace => f.write_str("no birth place"),
+            Gap::DeathDate => f.write_str("no death date"),
+            Gap::DeathPlace => f.write_str("no death place"),
+            Gap::Parents => f.write_str("no parents"),
+            Gap::Spouses => f.write_str("no spouses"),
+            Gap::Marriage => f.write_str("no marriage"),
+            Gap::Source(event) => write!(f, "{event} event without a source"),
+        }
+    }
+}
+
+/// Research gaps of an individual or a family
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RecordGaps {
+    /// xref of the record
+    pub xref: Xref,
+    /// tag of the record type, `INDI` or `FAM`
+    pub record_type: &'static str,
+    /// what is missing, events in record order
+    pub gaps: Vec<Gap>,
+}
+
+/// Totals of a `CompletenessReport`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CompletenessSummary {
+    /// number of individuals
+    pub individuals: usize,
+    /// individuals without a birth date
+    pub missing_birth_dates: usize,
+    /// individuals without a birth place
+    pub missing_birth_places: usize,
+    /// individuals without a death date
+    pub missing_death_dates: usize,
+    /// individuals without a death place
+    pub missing_death_places: usize,
+    /// individuals who are no child of any family
+    pub brick_walls: usize,
+    /// number of families
+    pub families: usize,
+    /// families without a husband or wife
+    pub missing_spouses: usize,
+    /// families without a marriage event
+    pub missing_marriages: usize,
+    /// events of individuals and families
+    pub events: usize,
+    /// events without a source citation
+    pub unsourced_events: usize,
+}
+
+impl CompletenessSummary {
+    /// Share of the events with at least one source citation, from 0 to 1,
+    /// or 1 without events
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sourced_share(&self) -> f64 {
+        if self.events == 0 {
+            1.0
+        } else {
+            (self.events - self.unsourced_events) as f64 / self.events as f64
+        }
+    }
+
+    /// Counts the `gaps` of an individual or a family
+    fn tally(&mut self, gaps: &[Gap]) {
+        for gap in gaps {
+            match gap {
+                Gap::BirthDate => self.missing_birth_dates += 1,
+                Gap::BirthPlace => self.missing_birth_places += 1,
+                Gap::DeathDate => self.missing_death_dates += 1,
+                Gap::DeathPlace => self.missing_death_places += 1,
+                Gap::Parents => self.brick_walls += 1,
+                Gap::Spouses => self.missing_spouses += 1,
+                Gap::Marriage => self.missing_marriages += 1,
+                Gap::Source(_) => self.unsourced_events += 1,
+            }
+        }
+    }
+}
+
+/// Outcome of `completeness`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CompletenessReport {
+    /// totals over the whole tree
+    pub summary: CompletenessSummary,
+    /// gaps of every individual, in record order
+    pub individuals: Vec<RecordGaps>,
+    /// gaps of every family, in record order
+    pub families: Vec<RecordGaps>,
+}
+
+impl CompletenessReport {
+    /// The report as a json string
+    ///
+    /// # Errors
+    /// * `serde_json::Error`
+    ///
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+impl fmt::Display for CompletenessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let summary = &self.summary;
+        writeln!(f, "individuals: {}", summary.individuals)?;
+        writeln!(f, "  missing birth dates: {}", summary.missing_birth_dates)?;
+        writeln!(f, "  missing birth places: {}", summary.missing_birth_places)?;
+        writeln!(f, "  missing death dates: {}", summary.missing_death_dates)?;
+        writeln!(f, "  missing death places: {}", summary.missing_death_places)?;
+        writeln!(f, "  brick walls: {}", summary.brick_walls)?;
+        writeln!(f, "families: {}", summary.families)?;
+        writeln!(f, "  missing spouses: {}", summary.missing_spouses)?;
+        writeln!(f, "  missing marriages: {}", summary.missing_marriages)?;
+        writeln!(f, "events: {}", summary.events)?;
+        writeln!(
+            f,
+            "  unsourced events: {} ({:.0}% sourced)",
+            summary.unsourced_events,
+            100.0 * summary.sourced_share()
+        )
+    }
+}
+
+/// Measure what is missing from the `GedcomData`
+///
+/// For each individual: birth and death dates and places, events without a
+/// source citation, and whether they are the child of any family ("brick
+/// walls"). For each family: a husband or wife, a marriage event and events
+/// without a source citation. A child of a family without spouses isn't a
+/// brick wall, the family is reported instead.
+/// The totals are in the `summary`.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+///
+#[must_use]
+pub fn completeness(tree: &GedcomData) -> CompletenessReport {
+    let mut report = CompletenessReport::default();
+
+    for (xref, indv) in &tree.individuals {
+        let gaps = individual_gaps(tree, xref, indv);
+        let summary = &mut report.summary;
+        summary.individuals += 1;
+        summary.events += indv.events().len();
+        summary.tally(&gaps.gaps);
+        report.individuals.push(gaps);
+    }
+    for (xref, fam) in &tree.families {
+        let gaps = family_gaps(xref, fam);
+        let summary = &mut report.summary;
+        summary.families += 1;
+        summary.events += fam.events().len();
+        summary.tally(&gaps.gaps);
+        report.families.push(gaps);
+    }
+
+    report
+}
+
+fn individual_gaps(tree: &GedcomData, xref: &Xref, indv: &Individual) -> RecordGaps {
+    let events = indv.events();
+    let has = |event_type: EventType, part: fn(&Event) -> bool| {
+        events.iter().any(|event| event.event == event_type && part(event))
+    };
+    let has_parents = indv.fam_child.keys().any(|xref_fam| tree.families.contains_key(xref_fam));
+
+    let checks = vec![
+        (Gap::BirthDate, has(EventType::Birth, |event| event.date.is_some())),
+        (Gap::BirthPlace, has(EventType::Birth, |event| event.place.is_some())),
+        (Gap::DeathDate, has(EventType::Death, |event| event.date.is_some())),
+        (Gap::DeathPlace, has(EventType::Death, |event| event.place.is_some())),
+        (Gap::Parents, has_parents),
+    ];
+    let mut gaps: Vec<Gap> = checks.into_iter().filter(|(_, found)| !found).map(|(gap, _)| gap).collect();
+    gaps.extend(unsourced(&events));
+
+    RecordGaps {
+        xref: xref.clone(),
+        record_type: "INDI",
+        gaps,
+    }
+}
+
+fn family_gaps(xref: &Xref, fam: &Family) -> RecordGaps {
+    let events = fam.events();
+    let mut gaps = Vec::new();
+    if fam.husbs.is_empty() && fam.wives.is_empty() {
+        gaps.push(Gap::Spouses);
+    }
+    if !events.iter().any(|event| event.event == EventType::Marriage) {
+        gaps.push(Gap::Marriage);
+    }
+    gaps.extend(unsourced(&events));
+
+    RecordGaps {
+        xref: xref.clone(),
+        record_type: "FAM",
+        gaps,
+    }
+}
+
+fn unsourced(events: &[Event]) -> impl Iterator<Item = Gap> + '_ {
+    events
+        .iter()
+        .filter(|event| event.citations.is_empty())
+        .map(|event| Gap::Source(event.event.clone()))
+}
@@ -0,0 +1,312 @@
+/*! Detection of individuals recorded more than once in the `GedcomData` type
+
+ */
+
+use crate::analyzer::connected_components;
+use crate::phonetic::{fold, soundex};
+use crate::tree::GedcomData;
+use crate::types::{event::HasEvents, DateValue, EventType, Gender, Individual, Xref};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Options of `find_duplicates`
+#[derive(Clone, Copy, Debug)]
+pub struct DuplicateOptions {
+    /// lowest score of the pairs returned
+    pub min_score: i32,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions { min_score: 40 }
+    }
+}
+
+/// Points given to a pair of individuals by each comparison. Negative points
+/// are evidence against the pair being the same person.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ScoreBreakdown {
+    /// share of the normalized given names and surnames in common, up to 30,
+    /// or -20 when the first given names neither sound alike nor are initials
+    /// of each other
+    pub name: i32,
+    /// 10 when the surnames have the same Soundex code
+    pub surname_sound: i32,
+    /// agreement of the birth dates and places, from -20 to 25
+    pub birth: i32,
+    /// agreement of the death dates and places, from -20 to 25
+    pub death: i32,
+    /// 10 for each parent or spouse in common, up to 30
+    pub relatives: i32,
+    /// -30 when both sexes are known and differ
+    pub sex: i32,
+}
+
+impl ScoreBreakdown {
+    /// Sum of the points
+    #[must_use]
+    pub fn total(&self) -> i32 {
+        self.name + self.surname_sound + self.birth + self.death + self.relatives + self.sex
+    }
+}
+
+/// A pair of individuals that may be the same person
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DuplicateCandidate {
+    /// the individual stored first
+    pub first: Xref,
+    /// the individual stored last
+    pub second: Xref,
+    /// total of the `breakdown`
+    pub score: i32,
+    /// points given by each comparison
+    pub breakdown: ScoreBreakdown,
+    /// both individuals belong to the same connected component. Duplicates
+    /// from combined files usually don't.
+    pub same_component: bool,
+}
+
+/// Find pairs of individuals that may be the same person
+///
+/// Individuals are compared by name, phonetic surname, birth and death dates
+/// and places, parents and spouses, and sex; see `ScoreBreakdown` for the
+/// points given. Only individuals whose surname (or given name, when they
+/// have no surname) share a Soundex code are compared, and spouses and
+/// parent and child are never paired.
+///
+/// Returns the pairs scoring at least `options.min_score`, highest first.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * options - minimum score of the pairs returned
+///
+#[must_use]
+pub fn find_duplicates(tree: &GedcomData, options: DuplicateOptions) -> Vec<DuplicateCandidate> {
+    find_duplicates_in(tree, &connected_components(tree), options)
+}
+
+/// `find_duplicates` with the connected components already computed
+pub(crate) fn find_duplicates_in(tree: &GedcomData,
+                                 components: &[HashSet<&Xref>],
+                                 options: DuplicateOptions) -> Vec<DuplicateCandidate> {
+    let component_of: HashMap<&Xref, usize> = components
+        .iter()
+        .enumerate()
+        .flat_map(|(i, component)| component.iter().map(move |xref| (*xref, i)))
+        .collect();
+
+    let profiles: Vec<Profile> = tree
+        .individuals
+        .iter()
+        .filter_map(|(xref, indv)| Profile::new(tree, xref, indv))
+        .collect();
+    let mut blocks: HashMap<(bool, &str), Vec<usize>> = HashMap::new();
+    for (i, profile) in profiles.iter().enumerate() {
+        blocks.entry((profile.surname, profile.code.as_str())).or_default().push(i);
+    }
+
+    let mut pairs = Vec::new();
+    for block in blocks.values() {
+        for (n, &i) in block.iter().enumerate() {
+            for &j in &block[n + 1..] {
+                let (a, b) = (&profiles[i], &profiles[j]);
+                if a.is_related_to(b) {
+                    continue;
+                }
+                let breakdown = a.compare(b);
+                if breakdown.total() >= options.min_score {
+                    pairs.push((i, j, breakdown));
+                }
+            }
+        }
+    }
+    pairs.sort_by_key(|(i, j, breakdown)| (-breakdown.total(), *i, *j));
+
+    pairs
+        .into_iter()
+        .map(|(i, j, breakdown)| {
+            let (a, b) = (&profiles[i], &profiles[j]);
+            DuplicateCandidate {
+                first: a.xref.clone(),
+                second: b.xref.clone(),
+                score: breakdown.total(),
+                breakdown,
+                same_component: component_of.get(a.xref) == component_of.get(b.xref),
+            }
+        })
+        .collect()
+}
+
+/// Date and place of an event, normalized for comparison
+struct EventFacts {
+    date: Option<DateValue>,
+    /// the comma separated parts of the place
+    place: Vec<String>,
+}
+
+/// Facts of an individual compared by `find_duplicates`
+struct Profile<'t> {
+    xref: &'t Xref,
+    first_given: Option<String>,
+    names: HashSet<String>,
+    surname: bool,
+    /// Soundex code of the surname, or of the given name if there's none
+    code: String,
+    sex: Option<bool>,
+    birth: Option<EventFacts>,
+    death: Option<EventFacts>,
+    parents: Vec<(&'t Xref, String)>,
+    spouses: Vec<(&'t Xref, String)>,
+}
+
+impl<'t> Profile<'t> {
+    fn new(tree: &'t GedcomData, xref: &'t Xref, indv: &'t Individual) -> Option<Profile<'t>> {
+        let (given, surname) = indv.given_and_surname()?;
+        let code = soundex(surname.as_deref().unwrap_or(&given))?;
+        let mut names: HashSet<String> = fold(&given).split_whitespace().map(str::to_string).collect();
+        let first_given = fold(&given).split_whitespace().next().map(str::to_string);
+        if let Some(surname) = &surname {
+            names.extend(fold(surname).split_whitespace().map(str::to_string));
+        }
+
+        let events = indv.events();
+        let facts = |event_type: EventType| {
+            events.iter().find(|event| event.event == event_type).map(|event| EventFacts {
+                date: event.date.as_deref().and_then(DateValue::parse),
+                place: event.place.as_deref().map(place_parts).unwrap_or_default(),
+            })
+        };
+
+        let relative = |xref: &'t Xref| {
+            let name = tree
+                .individuals
+                .get(xref)
+                .and_then(Individual::given_and_surname)
+                .map(|(given, surname)| fold(&format!("{given} {}", surname.unwrap_or_default())))
+                .unwrap_or_default();
+            (xref, name.split_whitespace().collect::<Vec<_>>().join(" "))
+        };
+        let mut parents = Vec::new();
+        for xref_fam in indv.fam_child.keys() {
+            if let Some(fam) = tree.families.get(xref_fam) {
+                parents.extend(fam.husbs.iter().chain(&fam.wives).map(relative));
+            }
+        }
+        let mut spouses = Vec::new();
+        for xref_fam in &indv.fam_spouse {
+            if let Some(fam) = tree.families.get(xref_fam) {
+                let others = fam.husbs.iter().chain(&fam.wives).filter(|spouse| *spouse != xref);
+                spouses.extend(others.map(relative));
+            }
+        }
+
+        Some(Profile {
+            xref,
+            first_given,
+            names,
+            surname: surname.is_some(),
+            code,
+            sex: match indv.sex {
+                Gender::Male => Some(true),
+                Gender::Female => Some(false),
+                _ => None,
+            },
+            birth: facts(EventType::Birth),
+            death: facts(EventType::Death),
+            parents,
+            spouses,
+        })
+    }
+
+    /// Spouses, or parent and child
+    fn is_related_to(&self, other: &Profile) -> bool {
+        let links = |a: &Profile, b: &Profile| {
+            a.parents.iter().chain(&a.spouses).any(|(xref, _)| *xref == b.xref)
+        };
+        links(self, other) || links(other, self)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn compare(&self, other: &Profile) -> ScoreBreakdown {
+        let shared = self.names.intersection(&other.names).count();
+        let all = self.names.union(&other.names).count().max(1);
+        // an initial, ie. `j` for `john`, doesn't contradict the full name
+        let other_given_name = match (&self.first_given, &other.first_given) {
+            (Some(a), Some(b)) => {
+                soundex(a) != soundex(b) && !a.starts_with(b.as_str()) && !b.starts_with(a.as_str())
+            }
+            _ => false,
+        };
+
+        ScoreBreakdown {
+            name: if other_given_name { -20 } else { (30 * shared / all) as i32 },
+            // individuals are only compared within the same Soundex code
+            surname_sound: if self.surname { 10 } else { 0 },
+            birth: compare_events(self.birth.as_ref(), other.birth.as_ref()),
+            death: compare_events(self.death.as_ref(), other.death.as_ref()),
+            relatives: 10 * (shared_relatives(&self.parents, &other.parents)
+                + shared_relatives(&self.spouses, &other.spouses))
+                .min(3),
+            sex: match (self.sex, other.sex) {
+                (Some(a), Some(b)) if a != b => -30,
+                _ => 0,
+            },
+        }
+    }
+}
+
+/// Points for the agreement of two events, see `ScoreBreakdown`
+fn compare_events(a: Option<&EventFacts>, b: Option<&EventFacts>) -> i32 {
+    let (Some(a), Some(b)) = (a, b) else {
+        return 0;
+    };
+
+    let date = match (a.date, b.date) {
+        (Some(x), Some(y)) => {
+            let years = (x.year - y.year).abs();
+            let approximate = x.approximate || y.approximate;
+            if years == 0 && x.day.is_some() && (x.month, x.day) == (y.month, y.day) {
+                15
+            } else if years == 0 {
+                10
+            } else if years <= 2 || (approximate && years <= 5) {
+                5
+            } else if years > 5 && !approximate || years > 10 {
+                -20
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    };
+    let place = if a.place.is_empty() || b.place.is_empty() {
+        0
+    } else if a.place == b.place {
+        10
+    } else if a.place[0] == b.place[0] {
+        5
+    } else {
+        0
+    };
+    date + place
+}
+
+/// Normalized comma separated parts of a place, ie. `["boston", "massachusetts"]`
+fn place_parts(place: &str) -> Vec<String> {
+    place
+        .split(',')
+        .map(|part| fold(part).split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Number of relatives in `a` that are also in `b`, by xref or by name
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn shared_relatives(a: &[(&Xref, String)], b: &[(&Xref, String)]) -> i32 {
+    a.iter()
+        .filter(|(xref, name)| {
+            b.iter().any(|(other, other_name)| xref == other || (!name.is_empty() && name == other_name))
+        })
+        .count() as i32
+}
@@ -0,0 +1,742 @@
+/*! Editing operations for the `GedcomData` type that keep links consistent
+
+ */
+
+use crate::analyzer::topological_sort;
+use crate::tree::{GedcomData, TreeError};
+use crate::validation::{validate, IssueKind};
+use crate::types::{
+    event::HasEvents, Association, ChangeDate, CustomData, Event, EventType, FamilyLink, FamilyLinkType,
+    Gender, Identifiers, Individual, LdsOrdinance, Name, OrdinanceType, SourceCitation, Xref,
+};
+use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Order in which `renumber_xrefs` numbers the individuals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XrefOrder {
+    /// the order the individuals were added in, ie. their order in the file
+    FileOrder,
+    /// ancestors before their descendants, see `analyzer::topological_sort`
+    Topological,
+    /// by surname then given names, ignoring case. Individuals without a
+    /// surname come last.
+    Surname,
+}
+
+/// Remove an individual and every pointer to them
+///
+/// The individual is removed from the `HUSB`, `WIFE` and `CHIL` lists of
+/// every family, and associations and aliases pointing to them are dropped.
+/// A family left without any husband, wife or child by the removal is
+/// removed as well, along with the links other records had to it.
+///
+/// Returns the removed individual.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData`
+/// * xref - xref of the individual to remove
+///
+/// # Errors
+///
+/// * `TreeError::NotFound` if there is no individual with `xref`
+///
+pub fn remove_individual(tree: &mut GedcomData, xref: &str) -> Result<Individual, TreeError> {
+    let (xref, indv) = tree
+        .individuals
+        .shift_remove_entry(xref)
+        .ok_or_else(|| TreeError::NotFound(xref.to_string()))?;
+    tree.remove_record_lines(xref.as_str());
+
+    let mut emptied = Vec::new();
+    for (xref_fam, fam) in &mut tree.families {
+        let members = fam.husbs.len() + fam.wives.len() + fam.children.len();
+        fam.husbs.retain(|x| x != &xref);
+        fam.wives.retain(|x| x != &xref);
+        fam.children.retain(|x| x != &xref);
+        let remaining = fam.husbs.len() + fam.wives.len() + fam.children.len();
+        if remaining == 0 && members > 0 {
+            emptied.push(xref_fam.clone());
+        }
+    }
+    for xref_fam in &emptied {
+        tree.families.shift_remove(xref_fam);
+        tree.remove_record_lines(xref_fam.as_str());
+    }
+
+    for other in tree.individuals.values_mut() {
+        other.associations.retain(|association| association.xref != xref);
+        other.aliases.retain(|alias| alias != &xref);
+        for xref_fam in &emptied {
+            other.fam_spouse.shift_remove(xref_fam);
+            other.fam_child.shift_remove(xref_fam);
+        }
+        for ordinance in &mut other.lds_ordinances {
+            if ordinance.family.as_ref().is_some_and(|fam| emptied.contains(fam)) {
+                ordinance.family = None;
+            }
+        }
+    }
+
+    Ok(indv)
+}
+
+/// Move a child from one family to another, updating both the `CHIL` lists
+/// of the families and the `FAMC` links of the child
+///
+/// The pedigree, status and notes of the child's link to `from` are kept on
+/// the new link to `to`.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData`
+/// * child - xref of the individual to move
+/// * from - xref of the family the child currently belongs to
+/// * to - xref of the family to move the child to
+///
+/// # Errors
+///
+/// * `TreeError::NotFound` if any of the records doesn't exist
+/// * `TreeError::NotAChild` if `child` isn't linked to `from` as a child
+///
+pub fn move_child(tree: &mut GedcomData, child: &str, from: &str, to: &str) -> Result<(), TreeError> {
+    let xref_child = key_of(&tree.individuals, child)?;
+    let xref_from = key_of(&tree.families, from)?;
+    let xref_to = key_of(&tree.families, to)?;
+
+    let indv = &mut tree.individuals[&xref_child];
+    let fam_from = &tree.families[&xref_from];
+    if !indv.fam_child.contains_key(&xref_from) && !fam_from.children.contains(&xref_child) {
+        return Err(TreeError::NotAChild {
+            child: xref_child,
+            family: xref_from,
+        });
+    }
+
+    let link = indv
+        .fam_child
+        .shift_remove(&xref_from)
+        .unwrap_or_else(|| FamilyLink::with_type(FamilyLinkType::Child));
+    indv.add_family(xref_to.clone(), link);
+
+    tree.families[&xref_from].children.retain(|x| x != &xref_child);
+    let fam_to = &mut tree.families[&xref_to];
+    if !fam_to.children.contains(&xref_child) {
+        fam_to.add_child(xref_child);
+    }
+    Ok(())
+}
+
+/// A change made, or that would be made, by `merge_individuals`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum IndividualChange {
+    /// the survivor had no name and takes the merged individual's
+    NameAdded(String),
+    /// a name of the merged individual the survivor doesn't have, kept as an
+    /// alternate name
+    AlternateNameAdded(String),
+    /// the survivor's sex was unknown and takes the merged individual's
+    SexAdded(Gender),
+    /// the survivor had no title and takes the merged individual's
+    TitleAdded(String),
+    /// an event the survivor doesn't have
+    EventAdded(EventType),
+    /// a citation of an event both individuals have
+    CitationAdded {
+        /// type of the event
+        event: EventType,
+        /// the cited source
+        source: Xref,
+    },
+    /// custom data, by tag
+    CustomDataAdded(String),
+    /// an association with the given individual
+    AssociationAdded(Xref),
+    /// an alias pointing to the given individual
+    AliasAdded(Xref),
+    /// an LDS ordinance the survivor doesn't have
+    OrdinanceAdded(OrdinanceType),
+    /// a unique id, user reference number or other identifier
+    IdentifierAdded(String),
+    /// the merged individual was changed later than the survivor, and its
+    /// change date is kept
+    ChangeDateUpdated(String),
+    /// a membership of the merged individual in a family, now the survivor's
+    FamilyRedirected {
+        /// the family
+        family: Xref,
+        /// `HUSB`, `WIFE` or `CHIL`
+        tag: &'static str,
+    },
+    /// an association or alias of another individual, now pointing to the survivor
+    PointerRedirected {
+        /// the individual holding the pointer
+        record: Xref,
+        /// `ASSO` or `ALIA`
+        tag: &'static str,
+    },
+}
+
+impl fmt::Display for IndividualChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndividualChange::NameAdded(name) => write!(f, "added name {name}"),
+            IndividualChange::AlternateNameAdded(name) => write!(f, "added alternate name {name}"),
+            IndividualChange::SexAdded(sex) => write!(f, "added sex {sex:?}"),
+            IndividualChange::TitleAdded(title) => write!(f, "added title {title}"),
+            IndividualChange::EventAdded(event) => write!(f, "added {event} event"),
+            IndividualChange::CitationAdded { event, source } => {
+                write!(f, "added citation of {source} to {event} event")
+            }
+            IndividualChange::CustomDataAdded(tag) => write!(f, "added custom data {tag}"),
+            IndividualChange::AssociationAdded(xref) => write!(f, "added association with {xref}"),
+            IndividualChange::AliasAdded(xref) => write!(f, "added alias {xref}"),
+            IndividualChange::OrdinanceAdded(ordinance) => write!(f, "added {ordinance:?} ordinance"),
+            IndividualChange::IdentifierAdded(id) => write!(f, "added identifier {id}"),
+            IndividualChange::ChangeDateUpdated(date) => write!(f, "updated change date to {date}"),
+            IndividualChange::FamilyRedirected { family, tag } => write!(f, "redirected {tag} of {family}"),
+            IndividualChange::PointerRedirected { record, tag } => write!(f, "redirected {tag} of {record}"),
+        }
+    }
+}
+
+/// Outcome of `merge_individuals`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IndividualMergeReport {
+    /// the individual kept
+    pub survivor: Xref,
+    /// the individual merged into the survivor and removed
+    pub merged: Xref,
+    /// the tree was left untouched
+    pub dry_run: bool,
+    /// changes to the survivor, then to the records pointing to the merged individual
+    pub changes: Vec<IndividualChange>,
+}
+
+/// Merge two individuals judged to be the same person into one record
+///
+/// The survivor keeps its name, sex and title, taking the merged
+/// individual's when it has none; the merged individual's other names are
+/// added as alternate names. The later change date is kept. Events, citations, custom data,
+/// associations, aliases, LDS ordinances and identifiers the survivor doesn't
+/// have are added to it; events of the same type, date and place are
+/// considered the same. Family memberships and every pointer to the merged
+/// individual are redirected to the survivor, and the merged individual is
+/// removed.
+///
+/// With `dry_run`, the tree is left untouched and only the report of what
+/// would change is returned.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData`
+/// * survivor - xref of the individual to keep
+/// * merged - xref of the individual to merge into the survivor
+/// * `dry_run` - only report the changes
+///
+/// # Errors
+///
+/// * `TreeError::NotFound` if either individual doesn't exist
+/// * `TreeError::MergeWithSelf` if `survivor` and `merged` are the same
+///
+pub fn merge_individuals(tree: &mut GedcomData,
+                         survivor: &str,
+                         merged: &str,
+                         dry_run: bool) -> Result<IndividualMergeReport, TreeError> {
+    let xref_keep = key_of(&tree.individuals, survivor)?;
+    let xref_drop = key_of(&tree.individuals, merged)?;
+    if xref_keep == xref_drop {
+        return Err(TreeError::MergeWithSelf(xref_keep));
+    }
+    let mut changes = Vec::new();
+    let facts = MergedFacts::new(
+        &tree.individuals[&xref_keep],
+        &tree.individuals[&xref_drop],
+        &xref_keep,
+        &mut changes,
+    );
+    redirected_pointers(tree, &xref_keep, &xref_drop, &mut changes);
+
+    let report = IndividualMergeReport {
+        survivor: xref_keep.clone(),
+        merged: xref_drop.clone(),
+        dry_run,
+        changes,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    if let Some(other) = tree.individuals.shift_remove(&xref_drop) {
+        tree.remove_record_lines(xref_drop.as_str());
+        let keep = &mut tree.individuals[&xref_keep];
+        facts.apply(keep);
+        keep.fam_spouse.extend(other.fam_spouse);
+        for (xref_fam, link) in other.fam_child {
+            keep.fam_child.entry(xref_fam).or_insert(link);
+        }
+    }
+
+    rewrite_pointers(tree, &|xref: &Xref| (xref == &xref_drop).then(|| xref_keep.clone()));
+    for fam in tree.families.values_mut() {
+        for members in [&mut fam.husbs, &mut fam.wives, &mut fam.children] {
+            let mut seen = HashSet::new();
+            members.retain(|xref| seen.insert(xref.clone()));
+        }
+    }
+    let keep = &mut tree.individuals[&xref_keep];
+    keep.aliases.retain(|alias| alias != &xref_keep);
+    keep.associations.retain(|association| association.xref != xref_keep);
+
+    Ok(report)
+}
+
+/// Facts of the survivor of `merge_individuals` once merged
+struct MergedFacts {
+    name: Option<Name>,
+    alternate_names: Vec<Name>,
+    sex: Gender,
+    title: Option<String>,
+    events: Vec<Event>,
+    custom_data: Vec<CustomData>,
+    associations: Vec<Association>,
+    aliases: Vec<Xref>,
+    ordinances: Vec<LdsOrdinance>,
+    identifiers: Identifiers,
+    change_date: Option<ChangeDate>,
+}
+
+impl MergedFacts {
+    /// Merges the facts of `other` into those of `keep`, recording the changes
+    fn new(keep: &Individual, other: &Individual, xref_keep: &Xref, changes: &mut Vec<IndividualChange>) -> Self {
+        let mut facts = MergedFacts {
+            name: keep.name.clone(),
+            alternate_names: keep.alternate_names.clone(),
+            sex: keep.sex,
+            title: keep.title.clone(),
+            events: keep.events(),
+            custom_data: keep.custom_data.clone(),
+            associations: keep.associations.clone(),
+            aliases: keep.aliases.clone(),
+            ordinances: keep.lds_ordinances.clone(),
+            identifiers: keep.identifiers.clone(),
+            change_date: keep.change_date.clone(),
+        };
+
+        for name in other.name.iter().chain(&other.alternate_names) {
+            if facts.name.is_none() {
+                changes.push(IndividualChange::NameAdded(name_text(name)));
+                facts.name = Some(name.clone());
+            } else if facts.name.as_ref() != Some(name) && !facts.alternate_names.contains(name) {
+                changes.push(IndividualChange::AlternateNameAdded(name_text(name)));
+                facts.alternate_names.push(name.clone());
+            }
+        }
+        if facts.sex == Gender::Unknown && other.sex != Gender::Unknown {
+            facts.sex = other.sex;
+            changes.push(IndividualChange::SexAdded(other.sex));
+        }
+        if let (None, Some(other_title)) = (&facts.title, &other.title) {
+            changes.push(IndividualChange::TitleAdded(other_title.clone()));
+            facts.title = Some(other_title.clone());
+        }
+
+        facts.merge_events(other.events(), changes);
+        for data in &other.custom_data {
+            if !facts.custom_data.contains(data) {
+                changes.push(IndividualChange::CustomDataAdded(data.tag.clone()));
+                facts.custom_data.push(data.clone());
+            }
+        }
+        for association in &other.associations {
+            let known = &association.xref == xref_keep
+                || facts.associations.iter().any(|a| {
+                    a.xref == association.xref && a.relationship == association.relationship
+                });
+            if !known {
+                changes.push(IndividualChange::AssociationAdded(association.xref.clone()));
+                facts.associations.push(association.clone());
+            }
+        }
+        for alias in &other.aliases {
+            if alias != xref_keep && !facts.aliases.contains(alias) {
+                changes.push(IndividualChange::AliasAdded(alias.clone()));
+                facts.aliases.push(alias.clone());
+            }
+        }
+        for ordinance in &other.lds_ordinances {
+            let known = facts.ordinances.iter().any(|o| {
+                o.ordinance == ordinance.ordinance && o.date == ordinance.date && o.temple == ordinance.temple
+            });
+            if !known {
+                changes.push(IndividualChange::OrdinanceAdded(ordinance.ordinance));
+                facts.ordinances.push(ordinance.clone());
+            }
+        }
+        facts.merge_identifiers(&other.identifiers, changes);
+
+        let keep_time = facts.change_date.as_ref().and_then(ChangeDate::timestamp);
+        let other_time = other.change_date.as_ref().and_then(ChangeDate::timestamp);
+        if let Some(change_date) = &other.change_date {
+            if other_time > keep_time || facts.change_date.is_none() {
+                let date = change_date.date.clone().unwrap_or_default();
+                changes.push(IndividualChange::ChangeDateUpdated(date));
+                facts.change_date = Some(change_date.clone());
+            }
+        }
+
+        facts
+    }
+
+    /// Events of the same type, date and place are the same event
+    fn merge_events(&mut self, events: Vec<Event>, changes: &mut Vec<IndividualChange>) {
+        for event in events {
+            let same = self.events.iter_mut().find(|e| {
+                e.event == event.event && e.date == event.date && e.place == event.place
+            });
+            if let Some(same) = same {
+                for citation in event.citations {
+                    if !same.citations.contains(&citation) {
+                        changes.push(IndividualChange::CitationAdded {
+                            event: event.event.clone(),
+                            source: citation.xref.clone(),
+                        });
+                        same.citations.push(citation);
+                    }
+                }
+            } else {
+                changes.push(IndividualChange::EventAdded(event.event.clone()));
+                self.events.push(event);
+            }
+        }
+    }
+
+    fn merge_identifiers(&mut self, other: &Identifiers, changes: &mut Vec<IndividualChange>) {
+        let identifiers = &mut self.identifiers;
+        for uid in &other.unique_ids {
+            if !identifiers.has_unique_id(uid) {
+                changes.push(IndividualChange::IdentifierAdded(uid.clone()));
+                identifiers.add_unique_id(uid.clone());
+            }
+        }
+        for reference in &other.user_references {
+            if !identifiers.has_user_reference(&reference.value) {
+                changes.push(IndividualChange::IdentifierAdded(reference.value.clone()));
+                identifiers.add_user_reference(reference.clone());
+            }
+        }
+        for (id, other_id) in [
+            (&mut identifiers.automated_record_id, &other.automated_record_id),
+            (&mut identifiers.ancestral_file_number, &other.ancestral_file_number),
+            (&mut identifiers.permanent_record_file_number, &other.permanent_record_file_number),
+        ] {
+            if let (None, Some(other_id)) = (&id, other_id) {
+                changes.push(IndividualChange::IdentifierAdded(other_id.clone()));
+                *id = Some(other_id.clone());
+            }
+        }
+    }
+
+    fn apply(self, keep: &mut Individual) {
+        keep.name = self.name;
+        keep.alternate_names = self.alternate_names;
+        keep.sex = self.sex;
+        keep.title = self.title;
+        let kept_events = keep.events_mut().len();
+        for (i, event) in self.events.into_iter().enumerate() {
+            if i < kept_events {
+                keep.events_mut()[i].citations = event.citations;
+            } else {
+                // individuals accept any number of events of each type
+                let _ = keep.add_event(event);
+            }
+        }
+        keep.custom_data = self.custom_data;
+        keep.associations = self.associations;
+        keep.aliases = self.aliases;
+        keep.lds_ordinances = self.ordinances;
+        keep.identifiers = self.identifiers;
+        keep.change_date = self.change_date;
+    }
+}
+
+/// Records the pointers to `xref_drop` that `merge_individuals` redirects
+fn redirected_pointers(tree: &GedcomData, xref_keep: &Xref, xref_drop: &Xref, changes: &mut Vec<IndividualChange>) {
+    for (xref_fam, fam) in &tree.families {
+        for (tag, members) in [("HUSB", &fam.husbs), ("WIFE", &fam.wives), ("CHIL", &fam.children)] {
+            if members.contains(xref_drop) {
+                changes.push(IndividualChange::FamilyRedirected {
+                    family: xref_fam.clone(),
+                    tag,
+                });
+            }
+        }
+    }
+    for (xref, indv) in &tree.individuals {
+        if xref == xref_keep || xref == xref_drop {
+            continue;
+        }
+        if indv.associations.iter().any(|association| &association.xref == xref_drop) {
+            changes.push(IndividualChange::PointerRedirected { record: xref.clone(), tag: "ASSO" });
+        }
+        if indv.aliases.contains(xref_drop) {
+            changes.push(IndividualChange::PointerRedirected { record: xref.clone(), tag: "ALIA" });
+        }
+    }
+}
+
+/// Change the xref of a record, rewriting every pointer to it
+///
+/// The record keeps its position in the tree.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData`
+/// * from - current xref of the record
+/// * to - new xref of the record
+///
+/// # Errors
+///
+/// * `TreeError::NotFound` if there is no record with `from`
+/// * `TreeError::XrefInUse` if a record already uses `to`
+///
+pub fn rename_xref(tree: &mut GedcomData, from: &str, to: Xref) -> Result<(), TreeError> {
+    if tree.contains_xref(to.as_str()) {
+        return Err(TreeError::XrefInUse(to));
+    }
+    if !tree.contains_xref(from) {
+        return Err(TreeError::NotFound(from.to_string()));
+    }
+
+    rename_key(&mut tree.individuals, from, &to);
+    rename_key(&mut tree.families, from, &to);
+    if let Some(submitter) = rename_key(&mut tree.submitters, from, &to) {
+        submitter.xref = Some(to.clone());
+    }
+    if let Some(repo) = rename_key(&mut tree.repositories, from, &to) {
+        repo.xref = Some(to.clone());
+    }
+    if let Some(source) = rename_key(&mut tree.sources, from, &to) {
+        source.xref = Some(to.clone());
+    }
+    if let Some(media) = rename_key(&mut tree.multimedia, from, &to) {
+        media.xref = Some(to.clone());
+    }
+    tree.rename_record_lines(from, &to);
+
+    rewrite_pointers(tree, &|xref: &Xref| (xref == from).then(|| to.clone()));
+    Ok(())
+}
+
+/// Give every record an xref of the canonical form `@I1@`, `@F1@`, `@S1@`,
+/// `@R1@`, `@U1@` (submitters) or `@M1@` (multimedia), rewriting every
+/// pointer to them
+///
+/// Individuals are numbered in `order`. With `XrefOrder::FileOrder`, so are
+/// families; otherwise families are numbered in the order of their spouses,
+/// followed by the families without any. Other records are numbered in file
+/// order. The records are stored in their new order.
+///
+/// Returns the old xrefs along with their new ones, in the new order.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData`
+/// * order - order in which to number the individuals
+///
+/// # Errors
+///
+/// * `TreeError::Cycle` if the tree has a cycle, with `XrefOrder::Topological`
+/// * `TreeError::DanglingCollision` if a pointer to a missing record uses
+///   one of the new xrefs, which would then point to the renumbered record.
+///   The tree is left unchanged.
+///
+pub fn renumber_xrefs(tree: &mut GedcomData, order: XrefOrder) -> Result<IndexMap<Xref, Xref>, TreeError> {
+    let individuals: Vec<&Xref> = match order {
+        XrefOrder::FileOrder => tree.individuals.keys().collect(),
+        XrefOrder::Topological => topological_sort(tree)?.into_iter().rev().collect(),
+        XrefOrder::Surname => {
+            let mut xrefs: Vec<&Xref> = tree.individuals.keys().collect();
+            xrefs.sort_by_cached_key(|xref| surname_key(&tree.individuals[*xref]));
+            xrefs
+        }
+    };
+    let mut families = IndexSet::new();
+    if order != XrefOrder::FileOrder {
+        for xref in &individuals {
+            let fam_spouse = &tree.individuals[*xref].fam_spouse;
+            families.extend(fam_spouse.iter().filter(|fam| tree.families.contains_key(*fam)));
+        }
+    }
+    families.extend(tree.families.keys());
+
+    let mut renames = IndexMap::new();
+    number(&mut renames, 'I', individuals);
+    number(&mut renames, 'F', families);
+    number(&mut renames, 'S', tree.sources.keys());
+    number(&mut renames, 'R', tree.repositories.keys());
+    number(&mut renames, 'U', tree.submitters.keys());
+    number(&mut renames, 'M', tree.multimedia.keys());
+
+    let generated: HashSet<&Xref> = renames.values().collect();
+    let submitter = tree.header.submitter_tag.as_deref().and_then(|xref| Xref::new(xref).ok());
+    let collision = validate(tree)
+        .into_iter()
+        .filter_map(|issue| match issue.kind {
+            IssueKind::DanglingPointer { target, .. } => Some(target),
+            _ => None,
+        })
+        .chain(submitter)
+        .find(|target| !renames.contains_key(target) && generated.contains(target));
+    if let Some(target) = collision {
+        return Err(TreeError::DanglingCollision(target));
+    }
+
+    rekey(&mut tree.individuals, &renames);
+    rekey(&mut tree.families, &renames);
+    rekey(&mut tree.sources, &renames);
+    rekey(&mut tree.repositories, &renames);
+    rekey(&mut tree.submitters, &renames);
+    rekey(&mut tree.multimedia, &renames);
+    for (xref, source) in &mut tree.sources {
+        source.xref = Some(xref.clone());
+    }
+    for (xref, repo) in &mut tree.repositories {
+        repo.xref = Some(xref.clone());
+    }
+    for (xref, submitter) in &mut tree.submitters {
+        submitter.xref = Some(xref.clone());
+    }
+    for (xref, media) in &mut tree.multimedia {
+        media.xref = Some(xref.clone());
+    }
+    tree.remap_record_lines(&renames);
+
+    rewrite_pointers(tree, &|xref: &Xref| renames.get(xref).cloned());
+    Ok(renames)
+}
+
+/// Rewrite every pointer in the tree for which `rename` returns a new xref.
+/// The keys of the record maps are left alone.
+pub(crate) fn rewrite_pointers(tree: &mut GedcomData, rename: &impl Fn(&Xref) -> Option<Xref>) {
+    let rewrite = |xref: &mut Xref| {
+        if let Some(new) = rename(xref) {
+            *xref = new;
+        }
+    };
+
+    for indv in tree.individuals.values_mut() {
+        indv.fam_spouse = indv
+            .fam_spouse
+            .drain(..)
+            .map(|xref| rename(&xref).unwrap_or(xref))
+            .collect();
+        indv.fam_child = indv
+            .fam_child
+            .drain(..)
+            .map(|(xref, link)| (rename(&xref).unwrap_or(xref), link))
+            .collect();
+        for association in &mut indv.associations {
+            rewrite(&mut association.xref);
+            rewrite_citations(&mut association.citations, &rewrite);
+        }
+        indv.aliases.iter_mut().for_each(rewrite);
+        rewrite_events(indv.events_mut(), &rewrite);
+        rewrite_ordinances(&mut indv.lds_ordinances, &rewrite);
+    }
+
+    for fam in tree.families.values_mut() {
+        fam.husbs.iter_mut().for_each(rewrite);
+        fam.wives.iter_mut().for_each(rewrite);
+        fam.children.iter_mut().for_each(rewrite);
+        rewrite_events(fam.events_mut(), &rewrite);
+        rewrite_ordinances(&mut fam.lds_ordinances, &rewrite);
+    }
+
+    for source in tree.sources.values_mut() {
+        for citation in source.repo_citations_mut() {
+            rewrite(&mut citation.xref);
+        }
+    }
+
+    if let Some(submitter) = &mut tree.header.submitter_tag {
+        if let Some(new) = Xref::new(submitter.as_str()).ok().and_then(|xref| rename(&xref)) {
+            *submitter = new.into();
+        }
+    }
+}
+
+fn rewrite_citations(citations: &mut [SourceCitation], rewrite: &impl Fn(&mut Xref)) {
+    for citation in citations {
+        rewrite(&mut citation.xref);
+    }
+}
+
+fn rewrite_events(events: &mut [Event], rewrite: &impl Fn(&mut Xref)) {
+    for event in events {
+        rewrite_citations(&mut event.citations, rewrite);
+    }
+}
+
+fn rewrite_ordinances(ordinances: &mut [LdsOrdinance], rewrite: &impl Fn(&mut Xref)) {
+    for ordinance in ordinances {
+        if let Some(family) = &mut ordinance.family {
+            rewrite(family);
+        }
+        rewrite_citations(&mut ordinance.citations, rewrite);
+    }
+}
+
+/// The `NAME` value of a name, or its parts when there's no value
+fn name_text(name: &Name) -> String {
+    name.value.clone().unwrap_or_else(|| {
+        let parts = [&name.given, &name.surname];
+        parts.iter().filter_map(|part| part.as_deref()).collect::<Vec<_>>().join(" ")
+    })
+}
+
+/// Numbers `xrefs` from 1 with `prefix`, ie. `@I1@`, `@I2@`, …
+fn number<'x>(renames: &mut IndexMap<Xref, Xref>, prefix: char, xrefs: impl IntoIterator<Item = &'x Xref>) {
+    for (n, xref) in xrefs.into_iter().enumerate() {
+        let new = Xref::new(format!("@{prefix}{}@", n + 1)).expect("generated xref is valid");
+        renames.insert(xref.clone(), new);
+    }
+}
+
+/// Replaces the keys of `records` by their new xref, in the order of `renames`
+fn rekey<V>(records: &mut IndexMap<Xref, V>, renames: &IndexMap<Xref, Xref>) {
+    let mut old = std::mem::take(records);
+    *records = renames
+        .iter()
+        .filter_map(|(from, to)| old.swap_remove(from).map(|record| (to.clone(), record)))
+        .collect();
+}
+
+/// Sort key of an individual by surname then given names
+fn surname_key(indv: &Individual) -> (bool, String, String) {
+    let (given, surname) = indv.given_and_surname().unwrap_or_default();
+    (
+        surname.is_none(),
+        surname.unwrap_or_default().to_lowercase(),
+        given.to_lowercase(),
+    )
+}
+
+/// Looks up the stored `Xref` key for `xref`
+fn key_of<V>(records: &IndexMap<Xref, V>, xref: &str) -> Result<Xref, TreeError> {
+    records
+        .get_key_value(xref)
+        .map(|(key, _)| key.clone())
+        .ok_or_else(|| TreeError::NotFound(xref.to_string()))
+}
+
+/// Replaces the key `from` by `to`, keeping the entry at the same position
+fn rename_key<'m, V>(records: &'m mut IndexMap<Xref, V>, from: &str, to: &Xref) -> Option<&'m mut V> {
+    let (index, _, value) = records.shift_remove_full(from)?;
+    records.shift_insert(index, to.clone(), value);
+    records.get_index_mut(index).map(|(_, value)| value)
+}
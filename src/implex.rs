@@ -0,0 +1,146 @@
+/*! Pedigree collapse (implex) of the ancestry of an individual
+
+ */
+
+use crate::numbering::{ahnentafel, Ahnentafel, NumberingOptions};
+use crate::tree::GedcomData;
+use crate::types::Xref;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Ancestor slots of one generation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct GenerationImplex {
+    /// 0 for the root, 1 for its parents
+    pub generation: usize,
+    /// number of ancestor positions in the generation, `2^generation`
+    pub slots: u128,
+    /// positions held by a known individual
+    pub filled: usize,
+    /// different individuals holding the filled positions
+    pub distinct: usize,
+}
+
+impl GenerationImplex {
+    /// Filled positions held by an individual who also holds another one
+    /// in the generation
+    #[must_use]
+    pub fn repeated(&self) -> usize {
+        self.filled - self.distinct
+    }
+
+    /// Share of the filled positions lost to repeated individuals, from 0
+    /// (no collapse) towards 1
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn implex(&self) -> f64 {
+        if self.filled == 0 {
+            0.0
+        } else {
+            self.repeated() as f64 / self.filled as f64
+        }
+    }
+}
+
+impl fmt::Display for GenerationImplex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "generation {}: {} slots, {} filled, {} distinct, {} repeated",
+            self.generation,
+            self.slots,
+            self.filled,
+            self.distinct,
+            self.repeated()
+        )
+    }
+}
+
+/// An ancestor holding more than one Ahnentafel position
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RepeatedAncestor {
+    /// the ancestor
+    pub xref: Xref,
+    /// every Ahnentafel number of the ancestor, lowest first
+    pub numbers: Vec<u128>,
+}
+
+/// Outcome of `implex`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ImplexReport {
+    /// the individual numbered 1
+    pub root: Xref,
+    /// every generation from the root up to the last one with an ancestor
+    pub generations: Vec<GenerationImplex>,
+    /// ancestors in more than one position, by their lowest number
+    pub repeated: Vec<RepeatedAncestor>,
+}
+
+impl fmt::Display for ImplexReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "pedigree collapse of {}", self.root)?;
+        for generation in &self.generations {
+            writeln!(f, "{generation}")?;
+        }
+        for ancestor in &self.repeated {
+            let numbers: Vec<String> = ancestor.numbers.iter().map(u128::to_string).collect();
+            writeln!(f, "{} at {}", ancestor.xref, numbers.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Count, generation by generation, how many ancestor positions of `xref`
+/// are held by distinct individuals, and list the ancestors holding more
+/// than one position with all their Ahnentafel numbers
+///
+/// Positions are numbered like `numbering::ahnentafel`. Returns `None` if
+/// `xref` isn't an individual.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual whose ancestry is analyzed
+/// * options - depth limit and pedigrees followed
+///
+#[must_use]
+pub fn implex(tree: &GedcomData, xref: &Xref, options: NumberingOptions) -> Option<ImplexReport> {
+    let numbering = ahnentafel(tree, xref, options)?;
+
+    let mut generations: Vec<(usize, HashSet<&Xref>)> = Vec::new();
+    let mut numbers_of: IndexMap<&Xref, Vec<u128>> = IndexMap::new();
+    // numbers are visited in increasing order, so generation by generation
+    for (number, ancestor) in &numbering.numbers {
+        let generation = Ahnentafel::generation(*number);
+        while generations.len() <= generation {
+            generations.push((0, HashSet::new()));
+        }
+        generations[generation].0 += 1;
+        generations[generation].1.insert(ancestor);
+        numbers_of.entry(ancestor).or_default().push(*number);
+    }
+
+    Some(ImplexReport {
+        root: numbering.root,
+        generations: generations
+            .into_iter()
+            .enumerate()
+            .map(|(generation, (filled, distinct))| GenerationImplex {
+                generation,
+                slots: 1 << generation,
+                filled,
+                distinct: distinct.len(),
+            })
+            .collect(),
+        repeated: numbers_of
+            .into_iter()
+            .filter(|(_, numbers)| numbers.len() > 1)
+            .map(|(xref, numbers)| RepeatedAncestor {
+                xref: xref.clone(),
+                numbers,
+            })
+            .collect(),
+    })
+}
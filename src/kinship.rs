@@ -0,0 +1,293 @@
+/*! Wright's coefficients of relationship and inbreeding, by path counting
+
+ */
+
+use crate::analyzer::{Cycle, CycleLink};
+use crate::numbering::parent_family;
+use crate::tree::{GedcomData, TreeError};
+use crate::types::Xref;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+/// Options of `relationship_coefficient` and `inbreeding_coefficient`
+#[derive(Clone, Copy, Debug)]
+pub struct KinshipOptions {
+    /// only count paths up to this many generations above each individual,
+    /// `None` for no limit. Paths multiply quickly through pedigree collapse,
+    /// and ancestors further up barely change the coefficients.
+    pub max_generations: Option<usize>,
+    /// only follow child-to-family links with a `Birth` pedigree, or no
+    /// pedigree at all
+    pub birth_only: bool,
+}
+
+impl Default for KinshipOptions {
+    fn default() -> Self {
+        KinshipOptions {
+            max_generations: Some(10),
+            birth_only: false,
+        }
+    }
+}
+
+/// Share of a coefficient coming from one common ancestor
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AncestorContribution {
+    /// the common ancestor
+    pub xref: Xref,
+    /// number of paths through the ancestor, each pair of lines from the
+    /// two individuals meeting only at the ancestor
+    pub paths: usize,
+    /// what the paths add to the coefficient
+    pub contribution: f64,
+}
+
+/// A coefficient and the common ancestors it comes from
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Coefficient {
+    /// the coefficient, from 0 to 1
+    pub value: f64,
+    /// common ancestors with at least one path, in the order they were
+    /// reached. Their contributions add up to `value`.
+    pub ancestors: Vec<AncestorContribution>,
+}
+
+/// Wright's coefficient of relationship between `xref` and `other`
+///
+/// Every pair of lines from the two individuals up to a common ancestor that
+/// share no one but that ancestor is a path of `n` individuals adding
+/// `(1/2)^(n - 1) * (1 + F)` to the sum, `F` being the inbreeding coefficient
+/// of the ancestor. The sum is divided by `sqrt((1 + Fx) * (1 + Fo))` with
+/// the inbreeding coefficients of the individuals. Siblings are related by
+/// 1/2 and first cousins by 1/8; an individual is related to itself by 1.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the first individual
+/// * other - the second individual
+/// * options - depth limit and pedigrees followed
+///
+/// # Errors
+///
+/// * `TreeError::NotFound` if either individual doesn't exist
+/// * `TreeError::Cycle` if an individual is their own ancestor in the
+///   ancestry of either, whatever the depth limit
+///
+pub fn relationship_coefficient(tree: &GedcomData,
+                                xref: &Xref,
+                                other: &Xref,
+                                options: KinshipOptions) -> Result<Coefficient, TreeError> {
+    let xref = key_of(tree, xref)?;
+    let other = key_of(tree, other)?;
+    if xref == other {
+        return Ok(Coefficient {
+            value: 1.0,
+            ancestors: Vec::new(),
+        });
+    }
+
+    let mut counter = PathCounter::new(tree, options);
+    counter.check_cycles(xref)?;
+    counter.check_cycles(other)?;
+    let mut kinship = counter.kinship(xref, other);
+    let scale = 2.0 / ((1.0 + counter.inbreeding(xref)) * (1.0 + counter.inbreeding(other))).sqrt();
+    kinship.value *= scale;
+    for ancestor in &mut kinship.ancestors {
+        ancestor.contribution *= scale;
+    }
+    Ok(kinship)
+}
+
+/// Wright's inbreeding coefficient of `xref`
+///
+/// The coefficient is the kinship of the parents: every path between them
+/// through a common ancestor, sharing no one but that ancestor, of `n`
+/// individuals adds `(1/2)^n * (1 + F)` to it, `F` being the inbreeding
+/// coefficient of the ancestor. The child of first cousins has a coefficient
+/// of 1/16. The parents are chosen like `numbering::ahnentafel` does.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual
+/// * options - depth limit and pedigrees followed
+///
+/// # Errors
+///
+/// * `TreeError::NotFound` if `xref` isn't an individual
+/// * `TreeError::Cycle` if an individual is their own ancestor in the
+///   ancestry of `xref`, whatever the depth limit
+///
+pub fn inbreeding_coefficient(tree: &GedcomData, xref: &Xref, options: KinshipOptions) -> Result<Coefficient, TreeError> {
+    let xref = key_of(tree, xref)?;
+    let mut counter = PathCounter::new(tree, options);
+    counter.check_cycles(xref)?;
+    Ok(match counter.parents(xref) {
+        Some((father, mother)) => counter.kinship(father, mother),
+        None => Coefficient {
+            value: 0.0,
+            ancestors: Vec::new(),
+        },
+    })
+}
+
+/// Looks up the stored `Xref` key of the individual `xref`
+fn key_of<'c>(tree: &'c GedcomData, xref: &Xref) -> Result<&'c Xref, TreeError> {
+    tree.individuals
+        .get_key_value(xref)
+        .map(|(key, _)| key)
+        .ok_or_else(|| TreeError::NotFound(xref.to_string()))
+}
+
+/// Lines between individuals and their ancestors, with the inbreeding
+/// coefficients of the ancestors computed so far
+struct PathCounter<'c> {
+    tree: &'c GedcomData,
+    options: KinshipOptions,
+    inbreeding: HashMap<&'c Xref, f64>,
+    /// ancestors whose own ancestry is known to have no cycle
+    acyclic: HashSet<&'c Xref>,
+}
+
+impl<'c> PathCounter<'c> {
+    fn new(tree: &'c GedcomData, options: KinshipOptions) -> Self {
+        PathCounter {
+            tree,
+            options,
+            inbreeding: HashMap::new(),
+            acyclic: HashSet::new(),
+        }
+    }
+
+    /// Father and mother of `xref`, when both are known
+    fn parents(&self, xref: &Xref) -> Option<(&'c Xref, &'c Xref)> {
+        let (_, fam) = parent_family(self.tree, xref, self.options.birth_only)?;
+        let (father, _) = self.tree.individuals.get_key_value(fam.husbs.first()?)?;
+        let (mother, _) = self.tree.individuals.get_key_value(fam.wives.first()?)?;
+        Some((father, mother))
+    }
+
+    /// Every line from `xref` up to each of its ancestors and itself, from
+    /// `xref` to the ancestor
+    fn lines(&self, xref: &'c Xref) -> IndexMap<&'c Xref, Vec<Vec<&'c Xref>>> {
+        let mut lines: IndexMap<&Xref, Vec<Vec<&Xref>>> = IndexMap::new();
+        let mut pending = vec![vec![xref]];
+        while let Some(line) = pending.pop() {
+            let last = line[line.len() - 1];
+            let depth = line.len() - 1;
+            let at_limit = self.options.max_generations.is_some_and(|max| depth >= max);
+            if !at_limit {
+                if let Some((_, fam)) = parent_family(self.tree, last, self.options.birth_only) {
+                    for parent in fam.husbs.first().into_iter().chain(fam.wives.first()) {
+                        if self.tree.individuals.contains_key(parent) {
+                            let mut line = line.clone();
+                            line.push(parent);
+                            pending.push(line);
+                        }
+                    }
+                }
+            }
+            lines.entry(last).or_default().push(line);
+        }
+        lines
+    }
+
+    /// Fails with the first cycle found in the ancestry of `xref`, following
+    /// the parents `lines` follows but without any depth limit
+    fn check_cycles(&mut self, xref: &'c Xref) -> Result<(), TreeError> {
+        let mut path = Vec::new();
+        self.visit_ancestors(xref, &mut path).map_err(TreeError::Cycle)
+    }
+
+    /// `path` holds the child-to-parent links from the individual the visit
+    /// started at up to `xref`
+    fn visit_ancestors(&mut self,
+                       xref: &'c Xref,
+                       path: &mut Vec<(&'c Xref, &'c Xref, &'c Xref)>) -> Result<(), Cycle> {
+        if self.acyclic.contains(xref) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|(child, _, _)| *child == xref) {
+            // parent-to-child order, from `xref` back down to itself
+            let links = path[start..]
+                .iter()
+                .rev()
+                .map(|(child, family, parent)| CycleLink {
+                    parent: (*parent).clone(),
+                    family: (*family).clone(),
+                    child: (*child).clone(),
+                })
+                .collect();
+            return Err(Cycle { links });
+        }
+
+        if let Some((xref_fam, fam)) = parent_family(self.tree, xref, self.options.birth_only) {
+            for parent in fam.husbs.first().into_iter().chain(fam.wives.first()) {
+                if let Some((parent, _)) = self.tree.individuals.get_key_value(parent) {
+                    path.push((xref, xref_fam, parent));
+                    self.visit_ancestors(parent, path)?;
+                    path.pop();
+                }
+            }
+        }
+        self.acyclic.insert(xref);
+        Ok(())
+    }
+
+    /// Coefficient of kinship of two different individuals: the chance that
+    /// alleles drawn from each are identical by descent
+    fn kinship(&mut self, xref: &'c Xref, other: &'c Xref) -> Coefficient {
+        let up = self.lines(xref);
+        let down = self.lines(other);
+        let mut coefficient = Coefficient {
+            value: 0.0,
+            ancestors: Vec::new(),
+        };
+
+        for (ancestor, up_lines) in &up {
+            let Some(down_lines) = down.get(ancestor) else {
+                continue;
+            };
+            let weight = 1.0 + self.inbreeding(ancestor);
+            let mut paths = 0;
+            let mut contribution = 0.0;
+            for up_line in up_lines {
+                let below = &up_line[..up_line.len() - 1];
+                for down_line in down_lines {
+                    if down_line.iter().any(|xref| below.contains(xref)) {
+                        continue;
+                    }
+                    paths += 1;
+                    // individuals on the path, the ancestor counted once
+                    let length = up_line.len() + down_line.len() - 1;
+                    contribution += 0.5_f64.powi(i32::try_from(length).unwrap_or(i32::MAX)) * weight;
+                }
+            }
+            if paths > 0 {
+                coefficient.value += contribution;
+                coefficient.ancestors.push(AncestorContribution {
+                    xref: (*ancestor).clone(),
+                    paths,
+                    contribution,
+                });
+            }
+        }
+        coefficient
+    }
+
+    /// Inbreeding coefficient of `xref`, the kinship of its parents
+    fn inbreeding(&mut self, xref: &'c Xref) -> f64 {
+        if let Some(coefficient) = self.inbreeding.get(xref) {
+            return *coefficient;
+        }
+        let coefficient = match self.parents(xref) {
+            Some((father, mother)) if father != mother => self.kinship(father, mother).value,
+            _ => 0.0,
+        };
+        self.inbreeding.insert(xref, coefficient);
+        coefficient
+    }
+}
@@ -8,7 +8,7 @@ use gedcom::GedcomData;
 let gedcom_data = parse("./tests/fixtures/sample.ged").unwrap();
 
 // output some stats on the gedcom contents
-gedcom_data.stats();
+print!("{}", gedcom_data.statistics(0));
 ```
 
 */
@@ -27,8 +27,38 @@ pub mod tokenizer;
 pub mod types;
 
 mod tree;
-pub use tree::GedcomData;
+pub use tree::{GedcomData, TreeError};
 
 pub mod analyzer;
 pub use analyzer::Analyzer;
 
+pub mod validation;
+
+pub mod repair;
+
+pub mod edit;
+
+pub mod builder;
+
+pub mod merge;
+
+pub mod phonetic;
+
+pub mod duplicates;
+
+pub mod relationship;
+
+pub mod numbering;
+
+pub mod implex;
+
+pub mod kinship;
+
+pub mod chronology;
+
+pub mod completeness;
+
+pub mod statistics;
+
+pub mod names;
+
@@ -0,0 +1,225 @@
+/*! Merging of one `GedcomData` into another
+
+ */
+
+use crate::edit::rewrite_pointers;
+use crate::tree::GedcomData;
+use crate::types::{Repository, Source, Xref};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Options of `merge`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MergeOptions {
+    /// reuse an existing source instead of importing an identical one
+    pub unify_sources: bool,
+    /// reuse an existing repository instead of importing an identical one
+    pub unify_repositories: bool,
+}
+
+/// What happened to an imported record whose xref changed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum MergeAction {
+    /// the xref was already used, so the record got a new one
+    Renumbered,
+    /// the record was identical to an existing one and wasn't imported
+    Unified,
+}
+
+/// An imported record whose xref changed, and every pointer to it with it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MergedXref {
+    /// tag of the record type, ie. `INDI`
+    pub record_type: &'static str,
+    /// xref of the record in the imported tree
+    pub original: Xref,
+    /// xref the record is now referred to by
+    pub xref: Xref,
+    /// why the xref changed
+    pub action: MergeAction,
+}
+
+impl fmt::Display for MergedXref {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.action {
+            MergeAction::Renumbered => "renumbered to",
+            MergeAction::Unified => "unified with",
+        };
+        write!(f, "{} {} {} {}", self.record_type, self.original, action, self.xref)
+    }
+}
+
+/// Outcome of `merge`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MergeReport {
+    /// number of records added to the tree
+    pub imported: usize,
+    /// imported records whose xref changed, in record order
+    pub changes: Vec<MergedXref>,
+}
+
+/// Import every record of `other` into `tree`
+///
+/// Records of `other` whose xref is already used in `tree` are renumbered
+/// with the first free xref sharing its prefix, ie. `@I1@` may become
+/// `@I4@`, and every pointer to them in the imported records is rewritten.
+/// With `options`, sources and repositories identical to one already in
+/// `tree` aren't imported and pointers to them are redirected to the existing
+/// record instead. The header of `tree` is kept.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData` to import into
+/// * other - the `GedcomData` to import
+/// * options - which records to unify
+///
+pub fn merge(tree: &mut GedcomData, mut other: GedcomData, options: MergeOptions) -> MergeReport {
+    let mut report = MergeReport::default();
+    let mut renames = HashMap::new();
+
+    // repositories first, so sources citing them compare equal once unified
+    if options.unify_repositories {
+        for (xref, repo) in &other.repositories {
+            let existing = tree.repositories.iter().find(|(_, r)| same_repository(r, repo));
+            if let Some((existing, _)) = existing {
+                renames.insert(xref.clone(), existing.clone());
+                report.push("REPO", xref, existing, MergeAction::Unified);
+            }
+        }
+    }
+    if options.unify_sources {
+        for (xref, source) in &other.sources {
+            let existing = tree
+                .sources
+                .iter()
+                .find(|(_, s)| same_source(s, source, &renames));
+            if let Some((existing, _)) = existing {
+                renames.insert(xref.clone(), existing.clone());
+                report.push("SOUR", xref, existing, MergeAction::Unified);
+            }
+        }
+    }
+    let unified: HashSet<Xref> = renames.keys().cloned().collect();
+
+    let mut taken: HashSet<Xref> = record_xrefs(tree)
+        .chain(record_xrefs(&other))
+        .map(|(xref, _)| xref.clone())
+        .collect();
+    let mut counters = HashMap::new();
+    for (xref, record_type) in record_xrefs(&other) {
+        if !unified.contains(xref) && tree.contains_xref(xref.as_str()) {
+            let new = free_xref(xref, &taken, &mut counters);
+            taken.insert(new.clone());
+            report.push(record_type, xref, &new, MergeAction::Renumbered);
+            renames.insert(xref.clone(), new);
+        }
+    }
+
+    rewrite_pointers(&mut other, &|xref: &Xref| renames.get(xref).cloned());
+    let new_xref = |xref: Xref| renames.get(&xref).cloned().unwrap_or(xref);
+
+    report.imported = other.submitters.len()
+        + other.individuals.len()
+        + other.families.len()
+        + other.repositories.len()
+        + other.sources.len()
+        + other.multimedia.len()
+        - unified.len();
+
+    for (xref, mut submitter) in other.submitters {
+        let xref = new_xref(xref);
+        submitter.xref = Some(xref.clone());
+        tree.submitters.insert(xref, submitter);
+    }
+    for (xref, indv) in other.individuals {
+        tree.individuals.insert(new_xref(xref), indv);
+    }
+    for (xref, fam) in other.families {
+        tree.families.insert(new_xref(xref), fam);
+    }
+    for (xref, mut repo) in other.repositories {
+        if !unified.contains(&xref) {
+            let xref = new_xref(xref);
+            repo.xref = Some(xref.clone());
+            tree.repositories.insert(xref, repo);
+        }
+    }
+    for (xref, mut source) in other.sources {
+        if !unified.contains(&xref) {
+            let xref = new_xref(xref);
+            source.xref = Some(xref.clone());
+            tree.sources.insert(xref, source);
+        }
+    }
+    for (xref, mut media) in other.multimedia {
+        let xref = new_xref(xref);
+        media.xref = Some(xref.clone());
+        tree.multimedia.insert(xref, media);
+    }
+
+    report
+}
+
+impl MergeReport {
+    fn push(&mut self, record_type: &'static str, original: &Xref, xref: &Xref, action: MergeAction) {
+        self.changes.push(MergedXref {
+            record_type,
+            original: original.clone(),
+            xref: xref.clone(),
+            action,
+        });
+    }
+}
+
+/// Xrefs of every record along with the tag of its type
+fn record_xrefs(tree: &GedcomData) -> impl Iterator<Item = (&Xref, &'static str)> {
+    tree.submitters
+        .keys()
+        .map(|xref| (xref, "SUBM"))
+        .chain(tree.individuals.keys().map(|xref| (xref, "INDI")))
+        .chain(tree.families.keys().map(|xref| (xref, "FAM")))
+        .chain(tree.repositories.keys().map(|xref| (xref, "REPO")))
+        .chain(tree.sources.keys().map(|xref| (xref, "SOUR")))
+        .chain(tree.multimedia.keys().map(|xref| (xref, "OBJE")))
+}
+
+/// First xref not in `taken` made of the prefix of `xref` and a number, ie.
+/// `@I4@` for `@I1@`
+fn free_xref(xref: &Xref, taken: &HashSet<Xref>, counters: &mut HashMap<String, usize>) -> Xref {
+    let prefix = xref.id().trim_end_matches(|c: char| c.is_ascii_digit()).to_string();
+    let counter = counters.entry(prefix.clone()).or_insert(0);
+    loop {
+        *counter += 1;
+        let candidate = Xref::new(format!("@{prefix}{counter}@")).expect("generated xref is valid");
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+fn same_repository(a: &Repository, b: &Repository) -> bool {
+    a.name == b.name && a.address == b.address
+}
+
+/// Sources are identical if their descriptions match and they're held by the
+/// same repositories, once `renames` is applied to the repositories of `b`
+fn same_source(a: &Source, b: &Source, renames: &HashMap<Xref, Xref>) -> bool {
+    let held_by = |source: &Source, renames: Option<&HashMap<Xref, Xref>>| {
+        source
+            .repo_citations()
+            .iter()
+            .map(|citation| {
+                let xref = renames
+                    .and_then(|renames| renames.get(&citation.xref))
+                    .unwrap_or(&citation.xref);
+                (xref.clone(), citation.call_number.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    a.title == b.title
+        && a.abbreviation == b.abbreviation
+        && a.data.agency == b.data.agency
+        && held_by(a, None) == held_by(b, Some(renames))
+}
@@ -0,0 +1,155 @@
+/*! Frequencies of surnames and given names in the `GedcomData` type
+
+ */
+
+use crate::phonetic::Encoding;
+use crate::tree::GedcomData;
+use crate::types::{event::HasEvents, DateValue, EventType};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// How often a name is used, and when and where its bearers were born
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NameCount {
+    /// the name as written
+    pub name: String,
+    /// number of individuals with the name
+    pub count: usize,
+    /// number of individuals with the name by century of birth, keyed by
+    /// its first year, ie. `1800` for 1800 to 1899
+    pub centuries: BTreeMap<i32, usize>,
+    /// number of individuals with the name by place of birth, as written
+    pub places: BTreeMap<String, usize>,
+}
+
+/// Names sharing a phonetic code
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NameGroup {
+    /// the code
+    pub code: String,
+    /// number of individuals with one of the names
+    pub count: usize,
+    /// the names, most frequent first
+    pub names: Vec<String>,
+}
+
+/// Outcome of `name_frequencies`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NameFrequencies {
+    /// surnames, most frequent first
+    pub surnames: Vec<NameCount>,
+    /// given names, most frequent first
+    pub given_names: Vec<NameCount>,
+}
+
+impl NameFrequencies {
+    /// The surnames grouped by their codes in `encoding`, most frequent
+    /// first. A surname with several codes is in the group of each.
+    #[must_use]
+    pub fn surname_groups(&self, encoding: Encoding) -> Vec<NameGroup> {
+        groups(&self.surnames, encoding)
+    }
+
+    /// The given names grouped by their codes in `encoding`, most frequent
+    /// first. A given name with several codes is in the group of each.
+    #[must_use]
+    pub fn given_name_groups(&self, encoding: Encoding) -> Vec<NameGroup> {
+        groups(&self.given_names, encoding)
+    }
+
+    /// The frequencies as a json string
+    ///
+    /// # Errors
+    /// * `serde_json::Error`
+    ///
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+/// Count the surnames and given names of the individuals of the `GedcomData`
+///
+/// Surnames are read from the `SURN` part of the name, or else from between
+/// the slashes of the `NAME` value, and given names likewise from `GIVN` or
+/// before the slashes. Each word of the given names counts as a name of its
+/// own, so `John Paul` counts for `John` and `Paul`. The birth, or else the
+/// christening, gives the century and place of the breakdowns. Ties are
+/// broken alphabetically.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+///
+#[must_use]
+pub fn name_frequencies(tree: &GedcomData) -> NameFrequencies {
+    let mut surnames = HashMap::new();
+    let mut given_names = HashMap::new();
+
+    for indv in tree.individuals.values() {
+        let Some((given, surname)) = indv.given_and_surname() else {
+            continue;
+        };
+        let events = indv.events();
+        let origin = [EventType::Birth, EventType::Christening]
+            .iter()
+            .find_map(|event_type| events.iter().find(|event| event.event == *event_type));
+        let century = origin
+            .and_then(|event| DateValue::parse(event.date.as_deref()?))
+            .map(|date| date.year.div_euclid(100) * 100);
+        let place = origin
+            .and_then(|event| event.place.as_deref())
+            .map(str::trim)
+            .filter(|place| !place.is_empty());
+
+        if let Some(surname) = surname.as_deref().map(str::trim).filter(|surname| !surname.is_empty()) {
+            count(&mut surnames, surname, century, place);
+        }
+        for given in given.split_whitespace() {
+            count(&mut given_names, given, century, place);
+        }
+    }
+
+    NameFrequencies {
+        surnames: most_frequent(surnames),
+        given_names: most_frequent(given_names),
+    }
+}
+
+fn count(counts: &mut HashMap<String, NameCount>, name: &str, century: Option<i32>, place: Option<&str>) {
+    let entry = counts.entry(name.to_string()).or_insert_with(|| NameCount {
+        name: name.to_string(),
+        ..NameCount::default()
+    });
+    entry.count += 1;
+    if let Some(century) = century {
+        *entry.centuries.entry(century).or_insert(0) += 1;
+    }
+    if let Some(place) = place {
+        *entry.places.entry(place.to_string()).or_insert(0) += 1;
+    }
+}
+
+fn most_frequent(counts: HashMap<String, NameCount>) -> Vec<NameCount> {
+    let mut counts: Vec<NameCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts
+}
+
+fn groups(names: &[NameCount], encoding: Encoding) -> Vec<NameGroup> {
+    let mut groups: HashMap<String, NameGroup> = HashMap::new();
+    // names are most frequent first, and stay so in their groups
+    for name in names {
+        for code in encoding.encode(&name.name) {
+            let group = groups.entry(code.clone()).or_insert_with(|| NameGroup {
+                code,
+                count: 0,
+                names: Vec::new(),
+            });
+            group.count += name.count;
+            group.names.push(name.name.clone());
+        }
+    }
+    let mut groups: Vec<NameGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+    groups
+}
@@ -0,0 +1,213 @@
+/*! Genealogical numbering of the ancestors and descendants of an individual
+
+ */
+
+use crate::analyzer::is_birth;
+use crate::tree::GedcomData;
+use crate::types::{Family, Xref};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Options of `ahnentafel` and `descendant_numbers`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NumberingOptions {
+    /// stop this many generations away from the root, `None` for no limit
+    pub max_generations: Option<usize>,
+    /// only follow child-to-family links with a `Birth` pedigree, or no
+    /// pedigree at all
+    pub birth_only: bool,
+}
+
+/// Ahnentafel (Sosa-Stradonitz) numbers of the ancestors of an individual
+///
+/// The root is 1, and the father and mother of `n` are `2n` and `2n + 1`.
+/// Numbers stop at generation 127, past which they don't fit in a `u128`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Ahnentafel {
+    /// the individual numbered 1
+    pub root: Xref,
+    /// every number and the individual in that position. Through pedigree
+    /// collapse an individual can hold more than one number.
+    pub numbers: BTreeMap<u128, Xref>,
+}
+
+impl Ahnentafel {
+    /// Generation of `number`, 0 for the root and 1 for its parents
+    #[must_use]
+    pub fn generation(number: u128) -> usize {
+        (u128::BITS - 1 - number.leading_zeros()) as usize
+    }
+
+    /// Numbers held by `xref`, lowest first
+    #[must_use]
+    pub fn numbers_of(&self, xref: &Xref) -> Vec<u128> {
+        self.numbers
+            .iter()
+            .filter(|(_, x)| *x == xref)
+            .map(|(number, _)| *number)
+            .collect()
+    }
+}
+
+/// Scheme of the numbers given by `descendant_numbers`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub enum DescendantScheme {
+    /// child positions separated by dots, ie. `1.3.2`
+    #[default]
+    DAboville,
+    /// child positions appended, ie. `132`, in parentheses from the tenth
+    /// child on, ie. `1(10)2` (modified Henry)
+    Henry,
+}
+
+/// d'Aboville or Henry numbers of the descendants of an individual
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DescendantNumbers {
+    /// the individual numbered `1`
+    pub root: Xref,
+    /// every number and the individual it was given to, in the order the
+    /// lines were followed. Descendants through more than one line, ie. from
+    /// a marriage of cousins, hold more than one number.
+    pub numbers: IndexMap<String, Xref>,
+}
+
+impl DescendantNumbers {
+    /// Numbers held by `xref`
+    #[must_use]
+    pub fn numbers_of(&self, xref: &Xref) -> Vec<&str> {
+        self.numbers
+            .iter()
+            .filter(|(_, x)| *x == xref)
+            .map(|(number, _)| number.as_str())
+            .collect()
+    }
+}
+
+/// Number the ancestors of `xref` with Ahnentafel numbers
+///
+/// The parents are the husband and wife of the family the individual is a
+/// birth child of, or else of the first family the individual is a child
+/// of. An ancestor reached through more than one line gets every number of
+/// those lines. Returns `None` if `xref` isn't an individual.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual numbered 1
+/// * options - depth limit and pedigrees followed
+///
+#[must_use]
+pub fn ahnentafel(tree: &GedcomData, xref: &Xref, options: NumberingOptions) -> Option<Ahnentafel> {
+    let (root, _) = tree.individuals.get_key_value(xref)?;
+    let mut numbers = BTreeMap::new();
+    let mut pending = vec![(root, 1_u128)];
+
+    while let Some((xref, number)) = pending.pop() {
+        numbers.insert(number, xref.clone());
+        let generation = Ahnentafel::generation(number);
+        if options.max_generations.is_some_and(|max| generation >= max) {
+            continue;
+        }
+        let Some((_, fam)) = parent_family(tree, xref, options.birth_only) else {
+            continue;
+        };
+        let parents = [(fam.husbs.first(), 0), (fam.wives.first(), 1)];
+        for (parent, offset) in parents {
+            let parent = parent.filter(|parent| tree.individuals.contains_key(*parent));
+            if let (Some(parent), Some(number)) = (parent, number.checked_mul(2)) {
+                pending.push((parent, number + offset));
+            }
+        }
+    }
+
+    Some(Ahnentafel {
+        root: root.clone(),
+        numbers,
+    })
+}
+
+/// Number the descendants of `xref` with d'Aboville or Henry numbers
+///
+/// Children are numbered in the order of the families the parent is a
+/// spouse in, then of the children in each family. A descendant reached
+/// through more than one line gets a number from each. Returns `None` if
+/// `xref` isn't an individual.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual numbered `1`
+/// * scheme - d'Aboville or Henry numbers
+/// * options - depth limit and pedigrees followed
+///
+#[must_use]
+pub fn descendant_numbers(tree: &GedcomData,
+                          xref: &Xref,
+                          scheme: DescendantScheme,
+                          options: NumberingOptions) -> Option<DescendantNumbers> {
+    let (root, _) = tree.individuals.get_key_value(xref)?;
+    let mut numbers = IndexMap::new();
+    // the individuals from the root down to each pending one, so that a
+    // cycle in the tree isn't followed forever
+    let mut pending = vec![("1".to_string(), vec![root])];
+
+    while let Some((number, line)) = pending.pop() {
+        let xref = line[line.len() - 1];
+        numbers.insert(number.clone(), xref.clone());
+        if options.max_generations.is_some_and(|max| line.len() > max) {
+            continue;
+        }
+
+        let mut children = Vec::new();
+        for xref_fam in &tree.individuals[xref].fam_spouse {
+            if let Some(fam) = tree.families.get(xref_fam) {
+                children.extend(fam.children.iter().filter(|xref_chld| {
+                    !options.birth_only || is_birth_child(tree, xref_chld, xref_fam)
+                }));
+            }
+        }
+        children.retain(|xref_chld| tree.individuals.contains_key(*xref_chld));
+
+        // pushed in reverse so that the first child is numbered next
+        for (position, child) in children.into_iter().enumerate().rev() {
+            if line.contains(&child) {
+                continue;
+            }
+            let position = position + 1;
+            let number = match scheme {
+                DescendantScheme::DAboville => format!("{number}.{position}"),
+                DescendantScheme::Henry if position < 10 => format!("{number}{position}"),
+                DescendantScheme::Henry => format!("{number}({position})"),
+            };
+            let mut line = line.clone();
+            line.push(child);
+            pending.push((number, line));
+        }
+    }
+
+    Some(DescendantNumbers {
+        root: root.clone(),
+        numbers,
+    })
+}
+
+/// The family `xref` is a birth child of, or else the first family it is a
+/// child of unless `birth_only`
+pub(crate) fn parent_family<'c>(tree: &'c GedcomData, xref: &Xref, birth_only: bool) -> Option<(&'c Xref, &'c Family)> {
+    let indv = tree.individuals.get(xref)?;
+    let birth = indv
+        .fam_child
+        .iter()
+        .find(|(_, link)| is_birth(link.pedigree()))
+        .map(|(xref_fam, _)| xref_fam);
+    let xref_fam = if birth_only { birth? } else { birth.or_else(|| indv.fam_child.keys().next())? };
+    tree.families.get_key_value(xref_fam)
+}
+
+fn is_birth_child(tree: &GedcomData, xref_chld: &Xref, xref_fam: &Xref) -> bool {
+    tree.individuals
+        .get(xref_chld)
+        .and_then(|child| child.fam_child.get(xref_fam))
+        .is_some_and(|link| is_birth(link.pedigree()))
+}
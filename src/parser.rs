@@ -7,10 +7,10 @@ use anyhow::{Context, Result};
 use crate::tokenizer::{Token, Tokenizer};
 use crate::tree::GedcomData;
 use crate::types::{
-    event::HasEvents, Address, CustomData, Event,
-    Family, FamilyLink, Gender, Header, Individual,
-    Name, RepoCitation, Repository, Source, SourceCitation,
-    Submitter
+    event::HasEvents, Address, Association, ChangeDate, ChildLinkStatus, CustomData, Event,
+    Family, FamilyLink, Gender, Header, Identifiers, Individual,
+    LdsOrdinance, Name, OrdinanceStatus, RepoCitation, Repository, Source, SourceCitation,
+    Submitter, UserReference, Xref
 };
 
 
@@ -57,13 +57,18 @@ pub struct Parser<'a> {
 impl<'a> Parser<'a> {
     /// Creates a parser state machine for parsing a gedcom file as a chars iterator
     #[must_use]
-    pub fn new(chars: Chars<'a>) -> Parser {
+    pub fn new(chars: Chars<'a>) -> Parser<'a> {
         let mut tokenizer = Tokenizer::new(chars);
         tokenizer.next_token();
         Parser { tokenizer }
     }
 
     /// Does the actual parsing of the record.
+    ///
+    /// # Errors
+    ///
+    /// * see `ParseError`
+    ///
     pub fn parse_record(&mut self) -> Result<GedcomData> {
         let mut data = GedcomData::default();
         loop {
@@ -74,30 +79,38 @@ impl<'a> Parser<'a> {
 
             self.tokenizer.next_token();
 
-            let mut pointer: Option<String> = None;
+            let mut pointer: Option<Xref> = None;
             if let Token::Pointer(xref) = &self.tokenizer.current_token {
-                pointer = Some(xref.to_string());
+                pointer = Some(Xref::new(xref.clone()).map_err(|_| self.token_error())?);
                 self.tokenizer.next_token();
             }
 
+            // records without an xref are kept under a generated one, and
+            // reported by `GedcomData::validate`
+            if let Token::Tag(tag) = &self.tokenizer.current_token {
+                if pointer.is_none() && matches!(tag.as_str(), "FAM" | "INDI" | "REPO" | "SOUR" | "SUBM") {
+                    pointer = Some(data.generate_missing_xref(tag));
+                }
+            }
+
+            if let Some(xref) = &pointer {
+                data.add_record_line(xref, self.tokenizer.line);
+            }
+
             if let Token::Tag(tag) = &self.tokenizer.current_token {
                 match tag.as_str() {
                     "HEAD" => data.header = self.parse_header()?,
-                    "FAM" => data.add_family(pointer,
-                                             self.parse_family(level)?),
-                    "INDI" => {
-                        data.add_individual(pointer,
-                                            self.parse_individual(level)?);
-                    }
-                    "REPO" => data.add_repository(self.parse_repository(level, pointer)?),
-                    "SOUR" => data.add_source(self.parse_source(level, pointer)?),
+                    "FAM" => data.add_family(pointer, self.parse_family(level)?)?,
+                    "INDI" => data.add_individual(pointer, self.parse_individual(level)?)?,
+                    "REPO" => data.add_repository(self.parse_repository(level, pointer)?)?,
+                    "SOUR" => data.add_source(self.parse_source(level, pointer)?)?,
                     "SUBM" => {
                         let submitter = self.parse_submitter(level, pointer)?;
-                        data.add_submitter(submitter);
+                        data.add_submitter(submitter)?;
                     },
                     "TRLR" => break,
                     _ => return Err(self.tag_error()),
-                    }
+                }
             }
             else if let Token::CustomTag(tag) = &self.tokenizer.current_token {
                 // TODO
@@ -113,7 +126,7 @@ impl<'a> Parser<'a> {
                 }
             } else {
                 return Err(self.token_error())
-            };
+            }
         }
 
         Ok(data)
@@ -176,7 +189,7 @@ impl<'a> Parser<'a> {
     }
 
     /// Parses SUBM top-level tag
-    fn parse_submitter(&mut self, level: u8, xref: Option<String>) -> Result<Submitter> {
+    fn parse_submitter(&mut self, level: u8, xref: Option<Xref>) -> Result<Submitter> {
         // skip over SUBM tag name
         self.tokenizer.next_token();
 
@@ -190,8 +203,16 @@ impl<'a> Parser<'a> {
                     }
                     "PHON" => submitter.phone = Some(self.take_line_value()?),
                     "COMM" => submitter.comments = Some(self.parse_comments(level + 1)?),
+                    "CHAN" => submitter.change_date = Some(self.parse_change_date(level + 1)?),
+                    "REFN" | "RIN" | "AFN" | "RFN" | "UID" => {
+                        let tag_clone = tag.clone();
+                        self.parse_identifier(tag_clone.as_str(), level + 1, &mut submitter.identifiers)?;
+                    }
                     _ => return Err(self.tag_error()),
                 },
+                Token::CustomTag(tag) if tag == "_UID" => {
+                    submitter.identifiers.add_unique_id(self.take_line_value()?);
+                }
                 Token::Level(_) => self.tokenizer.next_token(),
                 _ => return Err(self.token_error()),
             }
@@ -209,7 +230,7 @@ impl<'a> Parser<'a> {
         while self.tokenizer.current_token != Token::Level(level) {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "NAME" => individual.name = Some(self.parse_name(level + 1)?),
+                    "NAME" => individual.add_name(self.parse_name(level + 1)?),
                     "SEX" => individual.sex = self.parse_gender()?,
                     "ADOP" | "BIRT" | "BAPM" | "BARM" | "BASM" | "BLES" | "BURI" | "CENS"
                     | "CHR" | "CHRA" | "CONF" | "CREM" | "DEAT" | "EMIG" | "FCOM" | "GRAD"
@@ -219,25 +240,29 @@ impl<'a> Parser<'a> {
                     }
                     "FAMC" | "FAMS" => {
                         let tag_clone = tag.clone();
-                        let xref = self.take_line_value()?;
+                        let xref = self.take_xref()?;
                         individual
                             .add_family(xref,
                                         self.parse_family_link(tag_clone.as_str(), level + 1)?);
                     }
-                    "CHAN" => {
-                        // assuming it always only has a single DATE subtag
-                        self.tokenizer.next_token(); // level
-                        self.tokenizer.next_token(); // DATE tag
-                        individual.last_updated = Some(self.take_line_value()?);
-                    }
+                    "CHAN" => individual.change_date = Some(self.parse_change_date(level + 1)?),
                     "TITL" => individual.title = Some(self.parse_indv_title()?),
-                    "REFN" => {
-                        // TODO
-                        self.tokenizer.next_token(); // skip the next two tokens
-                        self.tokenizer.next_token(); //
-                    },
+                    "ASSO" => individual.add_association(self.parse_association(level + 1)?),
+                    "BAPL" | "CONL" | "ENDL" | "SLGC" => {
+                        let tag_clone = tag.clone();
+                        individual.add_lds_ordinance(
+                            self.parse_lds_ordinance(tag_clone.as_str(), level + 1)?);
+                    }
+                    "ALIA" => individual.add_alias(self.take_xref()?),
+                    "REFN" | "RIN" | "AFN" | "RFN" | "UID" => {
+                        let tag_clone = tag.clone();
+                        self.parse_identifier(tag_clone.as_str(), level + 1, &mut individual.identifiers)?;
+                    }
                     _ => return Err(self.tag_error()),
                 },
+                Token::CustomTag(tag) if tag == "_UID" => {
+                    individual.identifiers.add_unique_id(self.take_line_value()?);
+                }
                 Token::CustomTag(tag) => {
                     let tag_clone = tag.clone();
                     individual.add_custom_data(self.parse_custom_tag(tag_clone)?);
@@ -260,9 +285,15 @@ impl<'a> Parser<'a> {
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
                     "MARR" => family.add_event(self.parse_event("MARR", level + 1)?)?,
-                    "HUSB" => family.add_husb(self.take_line_value()?),
-                    "WIFE" => family.add_wife(self.take_line_value()?),
-                    "CHIL" => family.add_child(self.take_line_value()?),
+                    "HUSB" => family.add_husb(self.take_xref()?),
+                    "WIFE" => family.add_wife(self.take_xref()?),
+                    "CHIL" => family.add_child(self.take_xref()?),
+                    "CHAN" => family.change_date = Some(self.parse_change_date(level + 1)?),
+                    "SLGS" => family.add_lds_ordinance(self.parse_lds_ordinance("SLGS", level + 1)?),
+                    "REFN" | "RIN" | "AFN" | "RFN" | "UID" => {
+                        let tag_clone = tag.clone();
+                        self.parse_identifier(tag_clone.as_str(), level + 1, &mut family.identifiers)?;
+                    }
                     "DIV" => {
                         // TODO
                         self.tokenizer.next_token(); // skip the next two tokens
@@ -270,6 +301,9 @@ impl<'a> Parser<'a> {
                     }
                     _ => return Err(self.tag_error()),
                 },
+                Token::CustomTag(tag) if tag == "_UID" => {
+                    family.identifiers.add_unique_id(self.take_line_value()?);
+                }
                 Token::Level(_) => self.tokenizer.next_token(),
                 _ => return Err(self.token_error()),
             }
@@ -279,7 +313,7 @@ impl<'a> Parser<'a> {
         Ok(family)
     }
 
-    fn parse_source(&mut self, level: u8, xref: Option<String>) -> Result<Source> {
+    fn parse_source(&mut self, level: u8, xref: Option<Xref>) -> Result<Source> {
         // skip SOUR tag
         self.tokenizer.next_token();
         let mut source = Source::new(xref);
@@ -303,8 +337,16 @@ impl<'a> Parser<'a> {
                     "ABBR" => source.abbreviation = Some(self.take_continued_text(level + 1)?),
                     "TITL" => source.title = Some(self.take_continued_text(level + 1)?),
                     "REPO" => source.add_repo_citation(self.parse_repo_citation(level + 1)?),
+                    "CHAN" => source.change_date = Some(self.parse_change_date(level + 1)?),
+                    "REFN" | "RIN" | "AFN" | "RFN" | "UID" => {
+                        let tag_clone = tag.clone();
+                        self.parse_identifier(tag_clone.as_str(), level + 1, &mut source.identifiers)?;
+                    }
                     _ => return Err(self.tag_error()),
                 },
+                Token::CustomTag(tag) if tag == "_UID" => {
+                    source.identifiers.add_unique_id(self.take_line_value()?);
+                }
                 Token::Level(_) => self.tokenizer.next_token(),
                 _ => return Err(self.token_error())
             }
@@ -315,13 +357,15 @@ impl<'a> Parser<'a> {
     }
 
     /// Parses REPO top-level tag.
-    fn parse_repository(&mut self, level: u8, xref: Option<String>) -> Result<Repository> {
+    fn parse_repository(&mut self, level: u8, xref: Option<Xref>) -> Result<Repository> {
         // skip REPO tag
         self.tokenizer.next_token();
         let mut repo = Repository {
             xref,
             name: None,
             address: None,
+            change_date: None,
+            identifiers: Identifiers::default(),
         };
         loop {
             if let Token::Level(cur_level) = self.tokenizer.current_token {
@@ -333,8 +377,16 @@ impl<'a> Parser<'a> {
                 Token::Tag(tag) => match tag.as_str() {
                     "NAME" => repo.name = Some(self.take_line_value()?),
                     "ADDR" => repo.address = Some(self.parse_address(level + 1)?),
+                    "CHAN" => repo.change_date = Some(self.parse_change_date(level + 1)?),
+                    "REFN" | "RIN" | "AFN" | "RFN" | "UID" => {
+                        let tag_clone = tag.clone();
+                        self.parse_identifier(tag_clone.as_str(), level + 1, &mut repo.identifiers)?;
+                    }
                     _ => return Err(self.tag_error()),
                 },
+                Token::CustomTag(tag) if tag == "_UID" => {
+                    repo.identifiers.add_unique_id(self.take_line_value()?);
+                }
                 Token::Level(_) => self.tokenizer.next_token(),
                 _ => return Err(self.token_error()),
             }
@@ -343,6 +395,73 @@ impl<'a> Parser<'a> {
         Ok(repo)
     }
 
+    /// Parses CHAN tag
+    fn parse_change_date(&mut self, level: u8) -> Result<ChangeDate> {
+        // skip CHAN tag
+        self.tokenizer.next_token();
+        let mut change_date = ChangeDate::default();
+
+        loop {
+            if let Token::Level(cur_level) = self.tokenizer.current_token {
+                if cur_level <= level {
+                    break;
+                }
+            }
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "DATE" => change_date.date = Some(self.take_line_value()?),
+                    // TIME is a subtag of DATE
+                    "TIME" => change_date.time = Some(self.take_line_value()?),
+                    "NOTE" => change_date.add_note(self.take_continued_text(level + 1)?),
+                    _ => return Err(self.tag_error()),
+                },
+                Token::Level(_) => self.tokenizer.next_token(),
+                _ => return Err(self.token_error()),
+            }
+        }
+
+        Ok(change_date)
+    }
+
+    /// Parses the REFN, RIN, AFN, RFN and UID identifier tags
+    fn parse_identifier(&mut self, tag: &str, level: u8, identifiers: &mut Identifiers) -> Result<()> {
+        match tag {
+            "REFN" => identifiers.add_user_reference(self.parse_user_reference(level)?),
+            "RIN" => identifiers.automated_record_id = Some(self.take_line_value()?),
+            "AFN" => identifiers.ancestral_file_number = Some(self.take_line_value()?),
+            "RFN" => identifiers.permanent_record_file_number = Some(self.take_line_value()?),
+            "UID" => identifiers.add_unique_id(self.take_line_value()?),
+            _ => return Err(self.tag_error()),
+        }
+        Ok(())
+    }
+
+    /// Parses REFN tag
+    fn parse_user_reference(&mut self, level: u8) -> Result<UserReference> {
+        let mut reference = UserReference {
+            value: self.take_line_value()?,
+            reference_type: None,
+        };
+
+        loop {
+            if let Token::Level(cur_level) = self.tokenizer.current_token {
+                if cur_level <= level {
+                    break;
+                }
+            }
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "TYPE" => reference.reference_type = Some(self.take_line_value()?),
+                    _ => return Err(self.tag_error()),
+                },
+                Token::Level(_) => self.tokenizer.next_token(),
+                _ => return Err(self.token_error()),
+            }
+        }
+
+        Ok(reference)
+    }
+
     fn parse_custom_tag(&mut self, tag: String) -> Result<CustomData> {
         let value = self.take_line_value()?;
         Ok(CustomData { tag, value })
@@ -386,7 +505,12 @@ impl<'a> Parser<'a> {
             }
             match &self.tokenizer.current_token {
                 Token::Tag(tag) => match tag.as_str() {
-                    "PEDI" => link.set_pedigree(self.take_line_value()?.as_str())?,
+                    "PEDI" => link.set_pedigree(self.take_line_value()?.as_str()),
+                    "STAT" => {
+                        let status = ChildLinkStatus::from_value(&self.take_line_value()?);
+                        link.set_status(status);
+                    }
+                    "NOTE" => link.add_note(self.take_continued_text(level + 1)?),
                     _ => return Err(self.tag_error()),
                 },
                 Token::Level(_) => self.tokenizer.next_token(),
@@ -397,8 +521,92 @@ impl<'a> Parser<'a> {
         Ok(link)
     }
 
+    /// Parses ASSO tag
+    fn parse_association(&mut self, level: u8) -> Result<Association> {
+        let mut association = Association::new(self.take_xref()?);
+
+        loop {
+            if let Token::Level(cur_level) = self.tokenizer.current_token {
+                if cur_level <= level {
+                    break;
+                }
+            }
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "RELA" => association.relationship = Some(self.take_line_value()?),
+                    "TYPE" => association.record_type = Some(self.take_line_value()?),
+                    "NOTE" => association.add_note(self.take_continued_text(level + 1)?),
+                    "SOUR" => association.add_citation(self.parse_citation(level + 1)?),
+                    _ => return Err(self.tag_error()),
+                },
+                Token::Level(_) => self.tokenizer.next_token(),
+                _ => return Err(self.token_error()),
+            }
+        }
+
+        Ok(association)
+    }
+
+    /// Parses the LDS ordinance tags BAPL, CONL, ENDL, SLGC and SLGS
+    fn parse_lds_ordinance(&mut self, tag: &str, level: u8) -> Result<LdsOrdinance> {
+        self.tokenizer.next_token();
+        let mut ordinance = LdsOrdinance::from_tag(tag)?;
+
+        loop {
+            if let Token::Level(cur_level) = self.tokenizer.current_token {
+                if cur_level <= level {
+                    break;
+                }
+            }
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "DATE" => ordinance.date = Some(self.take_line_value()?),
+                    "TEMP" => ordinance.temple = Some(self.take_line_value()?),
+                    "PLAC" => ordinance.place = Some(self.take_line_value()?),
+                    "STAT" => {
+                        let (status, date) = self.parse_ordinance_status(level + 1)?;
+                        ordinance.status = Some(status);
+                        ordinance.status_date = date;
+                    }
+                    "FAMC" => ordinance.family = Some(self.take_xref()?),
+                    "NOTE" => ordinance.add_note(self.take_continued_text(level + 1)?),
+                    "SOUR" => ordinance.add_citation(self.parse_citation(level + 1)?),
+                    _ => return Err(self.tag_error()),
+                },
+                Token::Level(_) => self.tokenizer.next_token(),
+                _ => return Err(self.token_error()),
+            }
+        }
+
+        Ok(ordinance)
+    }
+
+    /// Parses the STAT tag of an LDS ordinance, along with the DATE of the status
+    fn parse_ordinance_status(&mut self, level: u8) -> Result<(OrdinanceStatus, Option<String>)> {
+        let status = OrdinanceStatus::from_value(&self.take_line_value()?);
+
+        let mut date = None;
+        loop {
+            if let Token::Level(cur_level) = self.tokenizer.current_token {
+                if cur_level <= level {
+                    break;
+                }
+            }
+            match &self.tokenizer.current_token {
+                Token::Tag(tag) => match tag.as_str() {
+                    "DATE" => date = Some(self.take_line_value()?),
+                    _ => return Err(self.tag_error()),
+                },
+                Token::Level(_) => self.tokenizer.next_token(),
+                _ => return Err(self.token_error()),
+            }
+        }
+
+        Ok((status, date))
+    }
+
     fn parse_repo_citation(&mut self, level: u8) -> Result<RepoCitation> {
-        let xref = self.take_line_value()?;
+        let xref = self.take_xref()?;
         let mut citation = RepoCitation {
             xref,
             call_number: None,
@@ -581,7 +789,7 @@ impl<'a> Parser<'a> {
 
     fn parse_citation(&mut self, level: u8) -> Result<SourceCitation> {
         let mut citation = SourceCitation {
-            xref: self.take_line_value()?,
+            xref: self.take_xref()?,
             page: None,
         };
         loop {
@@ -639,7 +847,7 @@ impl<'a> Parser<'a> {
         self.tokenizer.next_token();
 
         if let Token::LineValue(val) = &self.tokenizer.current_token {
-            value = val.to_string();
+            value = val.clone();
         } else {
             return Err(self.token_error())
         }
@@ -647,6 +855,19 @@ impl<'a> Parser<'a> {
         Ok(value)
     }
 
+    /// Grabs the pointer at the end of the current line as an `Xref`
+    fn take_xref(&mut self) -> Result<Xref> {
+        self.tokenizer.next_token();
+
+        let xref = if let Token::LineValue(val) = &self.tokenizer.current_token {
+            Xref::new(val.trim()).map_err(|_| self.value_error())?
+        } else {
+            return Err(self.token_error())
+        };
+        self.tokenizer.next_token();
+        Ok(xref)
+    }
+
     /// Debug function displaying GEDCOM line number of error message.
     fn dbg(&self) -> String {
         format!("line {}:", self.tokenizer.line)
@@ -0,0 +1,395 @@
+/*! Phonetic codes and normalization for comparing names
+
+ */
+
+use serde::Serialize;
+
+/// Lowercase `name`, fold common accented Latin letters to their base letter
+/// and replace punctuation by spaces, so that `Müller-Lüdenscheidt` and
+/// `muller ludenscheidt` compare equal once split on whitespace
+#[must_use]
+pub fn fold(name: &str) -> String {
+    let mut folded = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let base = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'æ' => "ae",
+            'ç' | 'ć' | 'č' => "c",
+            'ď' | 'đ' => "d",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' => "i",
+            'ł' | 'ľ' | 'ĺ' => "l",
+            'ñ' | 'ń' | 'ň' => "n",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+            'œ' => "oe",
+            'ř' | 'ŕ' => "r",
+            'ś' | 'š' | 'ş' => "s",
+            'ß' => "ss",
+            'ť' | 'ţ' => "t",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+            'ý' | 'ÿ' => "y",
+            'ź' | 'ż' | 'ž' => "z",
+            _ if c.is_alphanumeric() => {
+                folded.push(c);
+                continue;
+            }
+            _ => " ",
+        };
+        folded.push_str(base);
+    }
+    folded
+}
+
+/// American Soundex code of `name`, ie. `R163` for `Robert` and `Rupert`
+///
+/// Letters are folded with `fold` first and anything else is ignored.
+/// Returns `None` if `name` has no letter A to Z.
+#[must_use]
+pub fn soundex(name: &str) -> Option<String> {
+    fn digit(c: char) -> Option<char> {
+        match c {
+            'b' | 'f' | 'p' | 'v' => Some('1'),
+            'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+            'd' | 't' => Some('3'),
+            'l' => Some('4'),
+            'm' | 'n' => Some('5'),
+            'r' => Some('6'),
+            _ => None,
+        }
+    }
+
+    let mut letters = fold(name).chars().filter(char::is_ascii_lowercase).collect::<Vec<_>>().into_iter();
+    let first = letters.next()?;
+    let mut code = first.to_ascii_uppercase().to_string();
+    let mut last = digit(first);
+    for c in letters {
+        match digit(c) {
+            Some(d) if last != Some(d) => {
+                code.push(d);
+                if code.len() == 4 {
+                    break;
+                }
+                last = Some(d);
+            }
+            Some(_) => (),
+            // `h` and `w` don't separate letters with the same code, vowels do
+            None if c == 'h' || c == 'w' => (),
+            None => last = None,
+        }
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    Some(code)
+}
+
+/// Codes of a Daitch–Mokotoff rule: at the start of the name, before a vowel,
+/// and anywhere else; empty when the letters aren't coded
+type DmCodes = [&'static str; 3];
+
+/// Daitch–Mokotoff rules, with the alternative codes of ambiguous letters
+const DM_RULES: &[(&str, DmCodes, Option<DmCodes>)] = &[
+    ("ai", ["0", "1", ""], None),
+    ("aj", ["0", "1", ""], None),
+    ("ay", ["0", "1", ""], None),
+    ("au", ["0", "7", ""], None),
+    ("a", ["0", "", ""], None),
+    ("b", ["7", "7", "7"], None),
+    ("chs", ["5", "54", "54"], None),
+    ("ch", ["5", "5", "5"], Some(["4", "4", "4"])),
+    ("ck", ["5", "5", "5"], Some(["45", "45", "45"])),
+    ("csz", ["4", "4", "4"], None),
+    ("czs", ["4", "4", "4"], None),
+    ("cz", ["4", "4", "4"], None),
+    ("cs", ["4", "4", "4"], None),
+    ("c", ["5", "5", "5"], Some(["4", "4", "4"])),
+    ("drz", ["4", "4", "4"], None),
+    ("drs", ["4", "4", "4"], None),
+    ("dsh", ["4", "4", "4"], None),
+    ("dsz", ["4", "4", "4"], None),
+    ("dzh", ["4", "4", "4"], None),
+    ("dzs", ["4", "4", "4"], None),
+    ("ds", ["4", "4", "4"], None),
+    ("dz", ["4", "4", "4"], None),
+    ("dt", ["3", "3", "3"], None),
+    ("d", ["3", "3", "3"], None),
+    ("ei", ["0", "1", ""], None),
+    ("ej", ["0", "1", ""], None),
+    ("ey", ["0", "1", ""], None),
+    ("eu", ["1", "1", ""], None),
+    ("e", ["0", "", ""], None),
+    ("fb", ["7", "7", "7"], None),
+    ("f", ["7", "7", "7"], None),
+    ("g", ["5", "5", "5"], None),
+    ("h", ["5", "5", ""], None),
+    ("ia", ["1", "", ""], None),
+    ("ie", ["1", "", ""], None),
+    ("io", ["1", "", ""], None),
+    ("iu", ["1", "", ""], None),
+    ("i", ["0", "", ""], None),
+    ("j", ["1", "1", "1"], Some(["4", "4", "4"])),
+    ("ks", ["5", "54", "54"], None),
+    ("kh", ["5", "5", "5"], None),
+    ("k", ["5", "5", "5"], None),
+    ("l", ["8", "8", "8"], None),
+    ("mn", ["66", "66", "66"], None),
+    ("m", ["6", "6", "6"], None),
+    ("nm", ["66", "66", "66"], None),
+    ("n", ["6", "6", "6"], None),
+    ("oi", ["0", "1", ""], None),
+    ("oj", ["0", "1", ""], None),
+    ("oy", ["0", "1", ""], None),
+    ("o", ["0", "", ""], None),
+    ("pf", ["7", "7", "7"], None),
+    ("ph", ["7", "7", "7"], None),
+    ("p", ["7", "7", "7"], None),
+    ("q", ["5", "5", "5"], None),
+    ("rs", ["94", "94", "94"], Some(["4", "4", "4"])),
+    ("rz", ["94", "94", "94"], Some(["4", "4", "4"])),
+    ("r", ["9", "9", "9"], None),
+    ("schtsch", ["2", "4", "4"], None),
+    ("schtsh", ["2", "4", "4"], None),
+    ("schtch", ["2", "4", "4"], None),
+    ("shtch", ["2", "4", "4"], None),
+    ("shtsh", ["2", "4", "4"], None),
+    ("stsch", ["2", "4", "4"], None),
+    ("scht", ["2", "43", "43"], None),
+    ("schd", ["2", "43", "43"], None),
+    ("shch", ["2", "4", "4"], None),
+    ("stch", ["2", "4", "4"], None),
+    ("strz", ["2", "4", "4"], None),
+    ("strs", ["2", "4", "4"], None),
+    ("stsh", ["2", "4", "4"], None),
+    ("szcz", ["2", "4", "4"], None),
+    ("szcs", ["2", "4", "4"], None),
+    ("sch", ["4", "4", "4"], None),
+    ("sht", ["2", "43", "43"], None),
+    ("shd", ["2", "43", "43"], None),
+    ("szt", ["2", "43", "43"], None),
+    ("szd", ["2", "43", "43"], None),
+    ("sh", ["4", "4", "4"], None),
+    ("sc", ["2", "4", "4"], None),
+    ("st", ["2", "43", "43"], None),
+    ("sd", ["2", "43", "43"], None),
+    ("sz", ["4", "4", "4"], None),
+    ("s", ["4", "4", "4"], None),
+    ("ttsch", ["4", "4", "4"], None),
+    ("ttch", ["4", "4", "4"], None),
+    ("ttsz", ["4", "4", "4"], None),
+    ("trch", ["4", "4", "4"], None),
+    ("tch", ["4", "4", "4"], None),
+    ("trz", ["4", "4", "4"], None),
+    ("trs", ["4", "4", "4"], None),
+    ("tsh", ["4", "4", "4"], None),
+    ("tts", ["4", "4", "4"], None),
+    ("ttz", ["4", "4", "4"], None),
+    ("tzs", ["4", "4", "4"], None),
+    ("tsz", ["4", "4", "4"], None),
+    ("th", ["3", "3", "3"], None),
+    ("ts", ["4", "4", "4"], None),
+    ("tc", ["4", "4", "4"], None),
+    ("tz", ["4", "4", "4"], None),
+    ("t", ["3", "3", "3"], None),
+    ("ui", ["0", "1", ""], None),
+    ("uj", ["0", "1", ""], None),
+    ("uy", ["0", "1", ""], None),
+    ("u", ["0", "", ""], None),
+    ("v", ["7", "7", "7"], None),
+    ("w", ["7", "7", "7"], None),
+    ("x", ["5", "54", "54"], None),
+    ("y", ["1", "", ""], None),
+    ("zhdzh", ["2", "4", "4"], None),
+    ("zdzh", ["2", "4", "4"], None),
+    ("zsch", ["4", "4", "4"], None),
+    ("zdz", ["2", "4", "4"], None),
+    ("zhd", ["2", "43", "43"], None),
+    ("zsh", ["4", "4", "4"], None),
+    ("zd", ["2", "43", "43"], None),
+    ("zh", ["4", "4", "4"], None),
+    ("zs", ["4", "4", "4"], None),
+    ("z", ["4", "4", "4"], None),
+];
+
+/// Daitch–Mokotoff Soundex codes of `name`, ie. `645740` for `Moskowitz` and
+/// `Moskovitz`
+///
+/// Ambiguous letters like `ch` or `ck` give several codes, the most likely
+/// first. Letters are folded with `fold` first and anything else is ignored.
+/// Returns nothing if `name` has no letter A to Z.
+#[must_use]
+pub fn daitch_mokotoff(name: &str) -> Vec<String> {
+    const LENGTH: usize = 6;
+
+    let letters: String = fold(name).chars().filter(char::is_ascii_lowercase).collect();
+    if letters.is_empty() {
+        return Vec::new();
+    }
+    // each branch is a code so far and the last code appended to it: a
+    // letter coded like the one before isn't coded again
+    let mut branches: Vec<(String, &str)> = vec![(String::new(), "-")];
+    let mut rest = letters.as_str();
+    let mut start = true;
+    while !rest.is_empty() {
+        let Some((pattern, codes, alternative)) =
+            DM_RULES.iter().filter(|(pattern, ..)| rest.starts_with(pattern)).max_by_key(|(pattern, ..)| pattern.len())
+        else {
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[pattern.len()..];
+        let position = if start {
+            0
+        } else if rest.starts_with(|c| "aeiou".contains(c)) {
+            1
+        } else {
+            2
+        };
+        start = false;
+
+        let mut next = Vec::new();
+        for (code, last) in &branches {
+            for codes in std::iter::once(codes).chain(alternative) {
+                let replacement = codes[position];
+                let mut code = code.clone();
+                if !last.ends_with(replacement) {
+                    code.push_str(replacement);
+                    code.truncate(LENGTH);
+                }
+                if !next.contains(&(code.clone(), replacement)) {
+                    next.push((code, replacement));
+                }
+            }
+        }
+        branches = next;
+    }
+
+    let mut codes = Vec::new();
+    for (mut code, _) in branches {
+        while code.len() < LENGTH {
+            code.push('0');
+        }
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+/// Rules of `phonetic_keys`: letters, the letters that must follow for the
+/// rule to apply if any, and the sounds they may stand for
+const KEY_RULES: &[(&str, Option<&str>, &[&str])] = &[
+    ("tsch", None, &["tS"]),
+    ("sch", None, &["S"]),
+    ("tch", None, &["tS"]),
+    ("ch", None, &["x", "S", "tS"]),
+    ("ck", None, &["k"]),
+    ("cz", None, &["tS"]),
+    ("cs", None, &["tS"]),
+    ("c", Some("eiy"), &["s", "ts", "tS"]),
+    ("c", None, &["k"]),
+    ("g", Some("eiy"), &["g", "dZ"]),
+    ("gh", None, &["g"]),
+    ("kh", None, &["x"]),
+    ("ph", None, &["f"]),
+    ("th", None, &["t"]),
+    ("sh", None, &["S"]),
+    ("sz", None, &["s", "S"]),
+    ("zs", None, &["Z"]),
+    ("zh", None, &["Z"]),
+    ("rz", None, &["rz", "Z"]),
+    ("tz", None, &["ts"]),
+    ("qu", None, &["k", "kv"]),
+    ("q", None, &["k"]),
+    ("x", None, &["ks"]),
+    ("w", None, &["v"]),
+    ("j", None, &["j", "dZ"]),
+    ("h", None, &[""]),
+    ("ei", None, &["aj", "i"]),
+    ("ey", None, &["aj", "i"]),
+    ("ie", None, &["i"]),
+    ("ae", None, &["e"]),
+    ("oe", None, &["e"]),
+    ("ou", None, &["u"]),
+    ("y", None, &["i"]),
+];
+
+/// Phonetic keys of `name`, ie. `Smidt` for `Schmidt` and `Shmidt`
+///
+/// Spellings of the same sound in the main European languages are merged,
+/// ambiguous ones like `ch` giving several keys, and vowels are approximated
+/// to `a`, `i` and `o`. The rules are a small set of this crate's own, in
+/// the spirit of Beider–Morse but without its language detection and rule
+/// tables, so the keys group variant spellings but aren't Beider–Morse
+/// codes. Letters are folded with `fold` first and anything else is ignored.
+/// Returns nothing if `name` has no letter A to Z.
+#[must_use]
+pub fn phonetic_keys(name: &str) -> Vec<String> {
+    // names with many ambiguous letters stop branching past this many keys
+    const MAX_KEYS: usize = 16;
+
+    let letters: String = fold(name).chars().filter(char::is_ascii_lowercase).collect();
+    let mut keys = vec![String::new()];
+    let mut rest = letters.as_str();
+    while let Some(c) = rest.chars().next() {
+        let rule = KEY_RULES.iter().find(|(pattern, following, _)| {
+            rest.starts_with(pattern)
+                && match following {
+                    Some(following) => rest[pattern.len()..].starts_with(|c| following.contains(c)),
+                    None => true,
+                }
+        });
+        let (sounds, length) = match rule {
+            Some((pattern, _, sounds)) => (*sounds, pattern.len()),
+            None => (&[][..], c.len_utf8()),
+        };
+        rest = &rest[length..];
+
+        let letter = c.to_string();
+        let sounds: Vec<&str> = if sounds.is_empty() { vec![letter.as_str()] } else { sounds.to_vec() };
+        let sounds = if keys.len() * sounds.len() > MAX_KEYS { &sounds[..1] } else { &sounds[..] };
+        keys = keys.iter().flat_map(|key| sounds.iter().map(move |sound| format!("{key}{sound}"))).collect();
+    }
+
+    let mut approximated = Vec::new();
+    for key in keys {
+        let mut sounds: Vec<char> = key
+            .chars()
+            .map(|c| match c {
+                'e' | 'i' => 'i',
+                'o' | 'u' => 'o',
+                c => c,
+            })
+            .collect();
+        // doubled letters sound as one
+        sounds.dedup();
+        let key: String = sounds.into_iter().collect();
+        if !key.is_empty() && !approximated.contains(&key) {
+            approximated.push(key);
+        }
+    }
+    approximated
+}
+
+/// A phonetic code to group names by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum Encoding {
+    /// `soundex`
+    Soundex,
+    /// `daitch_mokotoff`
+    DaitchMokotoff,
+    /// `phonetic_keys`
+    PhoneticKeys,
+}
+
+impl Encoding {
+    /// Codes of `name` in this encoding, a single one for Soundex and
+    /// nothing if `name` has no letter A to Z
+    #[must_use]
+    pub fn encode(self, name: &str) -> Vec<String> {
+        match self {
+            Encoding::Soundex => soundex(name).into_iter().collect(),
+            Encoding::DaitchMokotoff => daitch_mokotoff(name),
+            Encoding::PhoneticKeys => phonetic_keys(name),
+        }
+    }
+}
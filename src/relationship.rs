@@ -0,0 +1,293 @@
+/*! Relationships in words between two individuals of the `GedcomData` type
+
+ */
+
+use crate::analyzer::{ancestors, TraversalOptions};
+use crate::tree::GedcomData;
+use crate::types::{Gender, Xref};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// How two individuals are related
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum RelationshipKind {
+    /// both xrefs are the same individual
+    Same,
+    /// the individuals share an ancestor, or one descends from the other
+    Blood {
+        /// generations from the first individual up to the common ancestors
+        up: usize,
+        /// generations from the common ancestors down to the second individual
+        down: usize,
+        /// the lines descend from a common ancestor through different families
+        half: bool,
+    },
+    /// the individuals are spouses in a family
+    Spouse,
+    /// related through the spouse of a parent, or a spouse's child
+    Step,
+    /// related through a spouse, ie. `brother-in-law`
+    InLaw,
+}
+
+/// Relationship of a second individual to a first one, see `relationship`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Relationship {
+    /// how the individuals are related
+    pub kind: RelationshipKind,
+    /// the most recent common ancestors of blood relatives, in record order
+    pub common_ancestors: Vec<Xref>,
+    /// what the second individual is to the first, ie. `great-grandaunt`
+    pub description: String,
+}
+
+impl fmt::Display for Relationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+/// Find the relationship of `other` to `xref` and describe it in words
+///
+/// Blood relationships are found through the most recent common ancestors:
+/// `great-grandaunt`, `2nd cousin once removed`, `half-brother`. Failing
+/// that, `other` may be a spouse, a step relative through the spouse of a
+/// parent or the child of a spouse (`step-mother`), or an in-law
+/// (`brother-in-law`, `husband of 1st cousin`, `spouse's niece`).
+///
+/// Returns `None` if either individual doesn't exist or they aren't related.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * xref - the individual the relationship is described from
+/// * other - the individual whose relationship is described
+///
+#[must_use]
+pub fn relationship(tree: &GedcomData, xref: &Xref, other: &Xref) -> Option<Relationship> {
+    let sex = tree.individuals.get(other)?.sex;
+    tree.individuals.get(xref)?;
+    if xref == other {
+        return Some(Relationship {
+            kind: RelationshipKind::Same,
+            common_ancestors: Vec::new(),
+            description: "self".to_string(),
+        });
+    }
+    let family = Family { tree };
+
+    if let Some(blood) = family.blood(xref, other) {
+        return Some(blood);
+    }
+    if family.spouses(xref).contains(&other) {
+        return Some(related(RelationshipKind::Spouse, gendered(sex, "husband", "wife", "spouse")));
+    }
+
+    // step relatives
+    let parents = family.parents(xref);
+    if parents.iter().any(|parent| family.spouses(parent).contains(&other)) {
+        let description = gendered(sex, "step-father", "step-mother", "step-parent");
+        return Some(related(RelationshipKind::Step, description));
+    }
+    if family.spouses(xref).iter().any(|spouse| family.children(spouse).contains(&other)) {
+        let description = gendered(sex, "step-son", "step-daughter", "step-child");
+        return Some(related(RelationshipKind::Step, description));
+    }
+    let step_parents = parents.iter().flat_map(|parent| family.spouses(parent));
+    if step_parents.into_iter().any(|step_parent| family.children(step_parent).contains(&other)) {
+        let description = gendered(sex, "step-brother", "step-sister", "step-sibling");
+        return Some(related(RelationshipKind::Step, description));
+    }
+
+    // in-laws, through a spouse of either individual
+    for spouse in family.spouses(xref) {
+        if let Some(blood) = family.blood(spouse, other) {
+            let description = match blood.kind {
+                RelationshipKind::Blood { up: 1, down: 0, .. } => {
+                    gendered(sex, "father-in-law", "mother-in-law", "parent-in-law")
+                }
+                RelationshipKind::Blood { up: 1, down: 1, .. } => {
+                    gendered(sex, "brother-in-law", "sister-in-law", "sibling-in-law")
+                }
+                _ => format!("spouse's {}", blood.description),
+            };
+            return Some(related(RelationshipKind::InLaw, description));
+        }
+    }
+    for spouse in family.spouses(other) {
+        if let Some(blood) = family.blood(xref, spouse) {
+            let description = match blood.kind {
+                RelationshipKind::Blood { up: 0, down: 1, .. } => {
+                    gendered(sex, "son-in-law", "daughter-in-law", "child-in-law")
+                }
+                RelationshipKind::Blood { up: 1, down: 1, .. } => {
+                    gendered(sex, "brother-in-law", "sister-in-law", "sibling-in-law")
+                }
+                _ => format!("{} of {}", gendered(sex, "husband", "wife", "spouse"), blood.description),
+            };
+            return Some(related(RelationshipKind::InLaw, description));
+        }
+    }
+
+    None
+}
+
+/// Parents, children and spouses in the tree
+struct Family<'c> {
+    tree: &'c GedcomData,
+}
+
+impl<'c> Family<'c> {
+    fn parents(&self, xref: &Xref) -> Vec<&'c Xref> {
+        let mut parents = Vec::new();
+        if let Some(indv) = self.tree.individuals.get(xref) {
+            for xref_fam in indv.fam_child.keys() {
+                if let Some(fam) = self.tree.families.get(xref_fam) {
+                    parents.extend(fam.husbs.iter().chain(&fam.wives));
+                }
+            }
+        }
+        parents
+    }
+
+    fn spouses(&self, xref: &Xref) -> Vec<&'c Xref> {
+        let mut spouses = Vec::new();
+        if let Some(indv) = self.tree.individuals.get(xref) {
+            for xref_fam in &indv.fam_spouse {
+                if let Some(fam) = self.tree.families.get(xref_fam) {
+                    spouses.extend(fam.husbs.iter().chain(&fam.wives).filter(|spouse| *spouse != xref));
+                }
+            }
+        }
+        spouses
+    }
+
+    fn children(&self, xref: &Xref) -> Vec<&'c Xref> {
+        let mut children = Vec::new();
+        if let Some(indv) = self.tree.individuals.get(xref) {
+            for xref_fam in &indv.fam_spouse {
+                if let Some(fam) = self.tree.families.get(xref_fam) {
+                    children.extend(&fam.children);
+                }
+            }
+        }
+        children
+    }
+
+    /// `xref` and its ancestors, each with the shortest path to it
+    fn ancestry(&self, xref: &'c Xref) -> HashMap<&'c Xref, Vec<&'c Xref>> {
+        let mut ancestry: HashMap<&Xref, Vec<&Xref>> = ancestors(self.tree, xref, TraversalOptions::default())
+            .map(|(ancestor, _, path)| (ancestor, path))
+            .collect();
+        ancestry.insert(xref, vec![xref]);
+        ancestry
+    }
+
+    /// The blood relationship of `other` to `xref`, through their most
+    /// recent common ancestors
+    fn blood(&self, xref: &Xref, other: &Xref) -> Option<Relationship> {
+        let (xref, _) = self.tree.individuals.get_key_value(xref)?;
+        let (other, indv) = self.tree.individuals.get_key_value(other)?;
+        let up_paths = self.ancestry(xref);
+        let down_paths = self.ancestry(other);
+
+        // nearest common ancestors, the up path breaking ties
+        let common = up_paths
+            .iter()
+            .filter_map(|(ancestor, up)| down_paths.get(ancestor).map(|down| (*ancestor, up, down)));
+        let (up, down) = common
+            .clone()
+            .map(|(_, up, down)| (up.len() - 1, down.len() - 1))
+            .min_by_key(|(up, down)| (up + down, *up))?;
+        let mut mrca: Vec<_> = common
+            .filter(|(_, up_path, down_path)| up_path.len() - 1 == up && down_path.len() - 1 == down)
+            .collect();
+        mrca.sort_by_key(|(ancestor, _, _)| self.tree.individuals.get_index_of(*ancestor));
+
+        // a single common ancestor reached through different families
+        let half = up > 0 && down > 0 && mrca.len() == 1 && {
+            let (ancestor, up_path, down_path) = mrca[0];
+            self.family_of(up_path[up - 1], ancestor) != self.family_of(down_path[down - 1], ancestor)
+        };
+
+        Some(Relationship {
+            kind: RelationshipKind::Blood { up, down, half },
+            common_ancestors: mrca.iter().map(|(ancestor, _, _)| (*ancestor).clone()).collect(),
+            description: describe_blood(up, down, half, indv.sex),
+        })
+    }
+
+    /// The family in which `xref` is a child of `parent`
+    fn family_of(&self, xref: &Xref, parent: &Xref) -> Option<&'c Xref> {
+        let indv = self.tree.individuals.get(xref)?;
+        indv.fam_child.keys().find_map(|xref_fam| {
+            let fam = self.tree.families.get(xref_fam)?;
+            let (xref_fam, _) = self.tree.families.get_key_value(xref_fam)?;
+            fam.husbs.iter().chain(&fam.wives).any(|x| x == parent).then_some(xref_fam)
+        })
+    }
+}
+
+fn related(kind: RelationshipKind, description: String) -> Relationship {
+    Relationship {
+        kind,
+        common_ancestors: Vec::new(),
+        description,
+    }
+}
+
+fn gendered(sex: Gender, male: &str, female: &str, neutral: &str) -> String {
+    match sex {
+        Gender::Male => male,
+        Gender::Female => female,
+        _ => neutral,
+    }
+    .to_string()
+}
+
+/// Words for a relative `down` generations below an ancestor `up`
+/// generations above, ie. `great-grandaunt` for 4 up and 1 down
+fn describe_blood(up: usize, down: usize, half: bool, sex: Gender) -> String {
+    let half = if half { "half-" } else { "" };
+    match (up, down) {
+        (_, 0) => format!("{}{}", greats(up, 2), gendered(sex, "father", "mother", "parent")),
+        (0, _) => format!("{}{}", greats(down, 2), gendered(sex, "son", "daughter", "child")),
+        (1, 1) => format!("{half}{}", gendered(sex, "brother", "sister", "sibling")),
+        (_, 1) => format!("{half}{}{}", greats(up, 3), gendered(sex, "uncle", "aunt", "uncle or aunt")),
+        (1, _) => {
+            format!("{half}{}{}", greats(down, 3), gendered(sex, "nephew", "niece", "nephew or niece"))
+        }
+        _ => {
+            let removed = match up.abs_diff(down) {
+                0 => String::new(),
+                1 => " once removed".to_string(),
+                2 => " twice removed".to_string(),
+                n => format!(" {n} times removed"),
+            };
+            format!("{half}{} cousin{removed}", ordinal(up.min(down) - 1))
+        }
+    }
+}
+
+/// Prefix for a relative `generations` away, where `grand` starts at
+/// `grand_at`: nothing, `grand`, `great-grand`, `2nd great-grand`, ...
+fn greats(generations: usize, grand_at: usize) -> String {
+    match generations.checked_sub(grand_at) {
+        None => String::new(),
+        Some(0) => "grand".to_string(),
+        Some(1) => "great-grand".to_string(),
+        Some(n) => format!("{} great-grand", ordinal(n)),
+    }
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
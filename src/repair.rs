@@ -0,0 +1,136 @@
+/*! Repairs for inconsistent links in the `GedcomData` type
+
+ */
+
+use crate::tree::GedcomData;
+use crate::types::{FamilyLink, FamilyLinkType, Gender, Xref};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A link added by `repair_family_links`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LinkRepair {
+    /// xref of the record the link was added to
+    pub record: Xref,
+    /// the tag of the added link, one of `CHIL`, `HUSB`, `WIFE`, `FAMC` or `FAMS`
+    pub tag: &'static str,
+    /// the record the added link points to
+    pub target: Xref,
+}
+
+impl fmt::Display for LinkRepair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "added {} {} to {}", self.tag, self.target, self.record)
+    }
+}
+
+/// Reconcile the links between individuals and families in both directions
+///
+/// A `FAMC` on an individual without a matching `CHIL` on the family (or the
+/// reverse) gets the missing side added, and likewise for `FAMS` and
+/// `HUSB`/`WIFE`. A spouse missing from a family is added as `HUSB` or
+/// `WIFE` according to their sex; if that is unknown they fill whichever role
+/// is still empty, preferring `HUSB`, and are left alone if both are taken.
+/// Links listed more than once are repaired once. Pointers to records that
+/// don't exist are left alone, see `validation::validate` to find them.
+///
+/// Returns the added links, in record order.
+///
+/// # Arguments
+///
+/// * tree - mutable reference to the `GedcomData`
+///
+pub fn repair_family_links(tree: &mut GedcomData) -> Vec<LinkRepair> {
+    let mut repairs = Vec::new();
+    // whether a repair already fills the HUSB and WIFE roles of a family
+    let mut filled: HashMap<&Xref, (bool, bool)> = HashMap::new();
+
+    // individual -> family
+    for (xref, indv) in &tree.individuals {
+        for xref_fam in &indv.fam_spouse {
+            if let Some(fam) = tree.families.get(xref_fam) {
+                let is_spouse = fam.husbs.contains(xref) || fam.wives.contains(xref);
+                if !is_spouse && !is_repaired(&repairs, xref_fam, &["HUSB", "WIFE"], xref) {
+                    let (husb, wife) = filled.entry(xref_fam).or_insert((false, false));
+                    let has_husb = *husb || !fam.husbs.is_empty();
+                    let has_wife = *wife || !fam.wives.is_empty();
+                    let tag = match indv.sex {
+                        Gender::Male => "HUSB",
+                        Gender::Female => "WIFE",
+                        _ if !has_husb => "HUSB",
+                        _ if !has_wife => "WIFE",
+                        _ => continue,
+                    };
+                    if tag == "HUSB" {
+                        *husb = true;
+                    } else {
+                        *wife = true;
+                    }
+                    repairs.push(repair(xref_fam, tag, xref));
+                }
+            }
+        }
+        for xref_fam in indv.fam_child.keys() {
+            if let Some(fam) = tree.families.get(xref_fam) {
+                if !fam.children.contains(xref) {
+                    repairs.push(repair(xref_fam, "CHIL", xref));
+                }
+            }
+        }
+    }
+
+    // family -> individual
+    for (xref, fam) in &tree.families {
+        for xref_spouse in fam.husbs.iter().chain(&fam.wives) {
+            if let Some(spouse) = tree.individuals.get(xref_spouse) {
+                if !spouse.fam_spouse.contains(xref) && !is_repaired(&repairs, xref_spouse, &["FAMS"], xref) {
+                    repairs.push(repair(xref_spouse, "FAMS", xref));
+                }
+            }
+        }
+        for xref_child in &fam.children {
+            if let Some(child) = tree.individuals.get(xref_child) {
+                if !child.fam_child.contains_key(xref) && !is_repaired(&repairs, xref_child, &["FAMC"], xref) {
+                    repairs.push(repair(xref_child, "FAMC", xref));
+                }
+            }
+        }
+    }
+
+    for change in &repairs {
+        let target = change.target.clone();
+        let fam = tree.families.get_mut(&change.record);
+        let indv = tree.individuals.get_mut(&change.record);
+        match (change.tag, fam, indv) {
+            ("HUSB", Some(fam), _) => fam.add_husb(target),
+            ("WIFE", Some(fam), _) => fam.add_wife(target),
+            ("CHIL", Some(fam), _) => fam.add_child(target),
+            ("FAMS", _, Some(indv)) => {
+                indv.add_family(target, FamilyLink::with_type(FamilyLinkType::Spouse));
+            }
+            ("FAMC", _, Some(indv)) => {
+                indv.add_family(target, FamilyLink::with_type(FamilyLinkType::Child));
+            }
+            _ => (),
+        }
+    }
+
+    repairs
+}
+
+/// Whether `repairs` already adds a link with one of `tags` from `record` to
+/// `target`
+fn is_repaired(repairs: &[LinkRepair], record: &Xref, tags: &[&str], target: &Xref) -> bool {
+    repairs
+        .iter()
+        .any(|change| change.record == *record && change.target == *target && tags.contains(&change.tag))
+}
+
+fn repair(record: &Xref, tag: &'static str, target: &Xref) -> LinkRepair {
+    LinkRepair {
+        record: record.clone(),
+        tag,
+        target: target.clone(),
+    }
+}
@@ -0,0 +1,195 @@
+/*! Summary statistics of the `GedcomData` type
+
+ */
+
+use crate::tree::GedcomData;
+use crate::types::{event::HasEvents, DateValue, Event, EventType, Gender};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Number of records of each type
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RecordCounts {
+    /// `SUBM` records
+    pub submitters: usize,
+    /// `INDI` records
+    pub individuals: usize,
+    /// `FAM` records
+    pub families: usize,
+    /// `REPO` records
+    pub repositories: usize,
+    /// `SOUR` records
+    pub sources: usize,
+    /// `OBJE` records
+    pub multimedia: usize,
+}
+
+/// Number of individuals of each gender
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GenderCounts {
+    /// `SEX M`
+    pub male: usize,
+    /// `SEX F`
+    pub female: usize,
+    /// `SEX N`
+    pub nonbinary: usize,
+    /// `SEX U` or no sex recorded
+    pub unknown: usize,
+}
+
+/// Summary statistics of a tree, see `statistics`
+///
+/// Displays as the record counts printed by `GedcomData::stats`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Statistics {
+    /// number of records of each type
+    pub records: RecordCounts,
+    /// number of events of individuals and families by event type, ie.
+    /// `Birth`
+    pub events: BTreeMap<String, usize>,
+    /// number of individuals of each gender
+    pub genders: GenderCounts,
+    /// year of the earliest dated event
+    pub first_year: Option<i32>,
+    /// year of the latest dated event
+    pub last_year: Option<i32>,
+    /// average age at death in years, over the individuals with a birth and a
+    /// death date
+    pub average_lifespan: Option<f64>,
+    /// number of families by their number of children
+    pub children_per_family: BTreeMap<usize, usize>,
+    /// most frequent surnames with their number of individuals, most
+    /// frequent first
+    pub top_surnames: Vec<(String, usize)>,
+    /// most frequent event places with their number of events, most
+    /// frequent first
+    pub top_places: Vec<(String, usize)>,
+}
+
+impl Statistics {
+    /// Average number of children of the families, `None` without families
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_children(&self) -> Option<f64> {
+        let families: usize = self.children_per_family.values().sum();
+        let children: usize = self.children_per_family.iter().map(|(children, count)| children * count).sum();
+        (families > 0).then(|| children as f64 / families as f64)
+    }
+
+    fn count_events(&mut self, events: &[Event], places: &mut HashMap<String, usize>) {
+        for event in events {
+            *self.events.entry(event.event.to_string()).or_insert(0) += 1;
+            if let Some(date) = event.date.as_deref().and_then(DateValue::parse) {
+                self.first_year = Some(self.first_year.map_or(date.year, |year| year.min(date.year)));
+                self.last_year = Some(self.last_year.map_or(date.year, |year| year.max(date.year)));
+            }
+            if let Some(place) = event.place.as_deref().map(str::trim).filter(|place| !place.is_empty()) {
+                *places.entry(place.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "----------------------")?;
+        writeln!(f, "| Gedcom Data Stats: |")?;
+        writeln!(f, "----------------------")?;
+        writeln!(f, "  submitters: {}", self.records.submitters)?;
+        writeln!(f, "  individuals: {}", self.records.individuals)?;
+        writeln!(f, "  families: {}", self.records.families)?;
+        writeln!(f, "  repositories: {}", self.records.repositories)?;
+        writeln!(f, "  sources: {}", self.records.sources)?;
+        writeln!(f, "  multimedia: {}", self.records.multimedia)?;
+        writeln!(f, "----------------------")
+    }
+}
+
+/// Compute summary statistics of the `GedcomData`
+///
+/// Surnames are read from the `SURN` part of the name, or else from between
+/// the slashes of the `NAME` value. Places are counted as written. Ties in
+/// the top lists are broken alphabetically.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+/// * top - length of the top surname and place lists
+///
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn statistics(tree: &GedcomData, top: usize) -> Statistics {
+    let mut stats = Statistics {
+        records: RecordCounts {
+            submitters: tree.submitters.len(),
+            individuals: tree.individuals.len(),
+            families: tree.families.len(),
+            repositories: tree.repositories.len(),
+            sources: tree.sources.len(),
+            multimedia: tree.multimedia.len(),
+        },
+        ..Statistics::default()
+    };
+    let mut surnames = HashMap::new();
+    let mut places = HashMap::new();
+    let mut lifespans = Vec::new();
+
+    for indv in tree.individuals.values() {
+        match indv.sex {
+            Gender::Male => stats.genders.male += 1,
+            Gender::Female => stats.genders.female += 1,
+            Gender::Nonbinary => stats.genders.nonbinary += 1,
+            Gender::Unknown => stats.genders.unknown += 1,
+        }
+        if let Some((_, Some(surname))) = indv.given_and_surname() {
+            let surname = surname.trim().to_string();
+            if !surname.is_empty() {
+                *surnames.entry(surname).or_insert(0) += 1;
+            }
+        }
+
+        let events = indv.events();
+        let date = |event_type: EventType| {
+            events
+                .iter()
+                .find(|event| event.event == event_type)
+                .and_then(|event| DateValue::parse(event.date.as_deref()?))
+        };
+        if let (Some(birth), Some(death)) = (date(EventType::Birth), date(EventType::Death)) {
+            if birth <= death {
+                lifespans.push(age(birth, death));
+            }
+        }
+        stats.count_events(&events, &mut places);
+    }
+    for fam in tree.families.values() {
+        *stats.children_per_family.entry(fam.children.len()).or_insert(0) += 1;
+        stats.count_events(&fam.events(), &mut places);
+    }
+
+    if !lifespans.is_empty() {
+        stats.average_lifespan = Some(f64::from(lifespans.iter().sum::<i32>()) / lifespans.len() as f64);
+    }
+    stats.top_surnames = most_frequent(surnames, top);
+    stats.top_places = most_frequent(places, top);
+    stats
+}
+
+/// Full years from `birth` to `death`, a missing month or day counting as
+/// earlier than any other
+fn age(birth: DateValue, death: DateValue) -> i32 {
+    let years = death.year - birth.year;
+    if (death.month, death.day) < (birth.month, birth.day) {
+        years - 1
+    } else {
+        years
+    }
+}
+
+fn most_frequent(counts: HashMap<String, usize>, top: usize) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts.truncate(top);
+    counts
+}
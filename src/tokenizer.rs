@@ -3,7 +3,7 @@ use std::str::Chars;
 // use thiserror::Error;
 // use anyhow::Result;
 
-/// Errors caught by tokenizer
+// Errors caught by tokenizer
 // #[derive(Error, Debug)]
 // pub enum TokenizerError {
 //     /// An error reporting the unknown token
@@ -53,7 +53,7 @@ pub struct Tokenizer<'a> {
 impl<'a> Tokenizer<'a> {
     /// Creates a new tokenizer for a char interator of gedcom file contents
     #[must_use]
-    pub fn new(chars: Chars<'a>) -> Tokenizer {
+    pub fn new(chars: Chars<'a>) -> Tokenizer<'a> {
         Tokenizer {
             current_char: '\n',
             current_token: Token::None,
@@ -69,6 +69,11 @@ impl<'a> Tokenizer<'a> {
     }
 
     /// Loads the next token into state
+    ///
+    /// # Panics
+    ///
+    /// * Unhandled token sequence
+    ///
     pub fn next_token(&mut self) {
         if self.current_char == '\0' {
             self.current_token = Token::EOF;
@@ -120,7 +125,7 @@ impl<'a> Tokenizer<'a> {
     fn extract_number(&mut self) -> u8 {
         self.skip_whitespace();
         let mut digits: Vec<char> = Vec::new();
-        while self.current_char.is_digit(10) {
+        while self.current_char.is_ascii_digit() {
             digits.push(self.current_char);
             self.next_char();
         }
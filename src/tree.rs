@@ -1,77 +1,499 @@
-
-use std::collections::HashMap;
-use crate::types::{Family, Header, Individual, Media, Repository, Source, Submitter};
+use indexmap::IndexMap;
+use crate::types::{
+    ChangeDate, EventType, Family, Header, IdentifierIndex, Identifiers, Individual, Media, Record, Repository, Source,
+    Submitter, Timestamp, Xref,
+};
+use crate::analyzer::Cycle;
+use crate::builder::GedcomBuilder;
+use crate::chronology::{check_chronology, ChronologyIssue, ChronologyOptions};
+use crate::completeness::{completeness, CompletenessReport};
+use crate::edit::{
+    merge_individuals, move_child, remove_individual, rename_xref, renumber_xrefs,
+    IndividualMergeReport, XrefOrder,
+};
+use crate::merge::{merge, MergeOptions, MergeReport};
+use crate::repair::{repair_family_links, LinkRepair};
+use crate::statistics::{statistics, Statistics};
+use crate::validation::{validate, ValidationIssue};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-// use std::collections::HashMap;
-type Xref = String;
+#[derive(Error, Debug)]
+/// Errors caught when modifying a `GedcomData`
+pub enum TreeError {
+    /// A record can't be stored without an xref to reference it by
+    #[error("{0} record has no xref")]
+    MissingXref(&'static str),
+    /// No record uses the given xref
+    #[error("no record with xref {0}")]
+    NotFound(String),
+    /// The xref is already used by another record
+    #[error("xref {0} is already in use")]
+    XrefInUse(Xref),
+    /// The individual isn't a child of the family
+    #[error("{child} is not a child of {family}")]
+    NotAChild {
+        /// the individual
+        child: Xref,
+        /// the family
+        family: Xref,
+    },
+    /// An individual can't be merged with itself
+    #[error("can't merge {0} with itself")]
+    MergeWithSelf(Xref),
+    /// A builder label was given to more than one record
+    #[error("label {0} is used by more than one record")]
+    DuplicateLabel(String),
+    /// A builder label doesn't refer to a record of the expected type
+    #[error("no individual labelled {0}")]
+    UnknownLabel(String),
+    /// The record can't have more than one event of the type
+    #[error("{xref} already has a {event} event")]
+    DuplicateEvent {
+        /// the record
+        xref: Xref,
+        /// the type of the event
+        event: EventType,
+    },
+    /// Parent-to-child links lead from an individual back to itself
+    #[error("tree has a cycle: {0}")]
+    Cycle(Cycle),
+    /// A pointer to a missing record would point to a record once renumbered
+    #[error("pointer to missing record {0} would point to a renumbered record")]
+    DanglingCollision(Xref),
+}
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 /// The data structure representing all the data within a gedcom file
+///
+/// Records are stored by their xref, so following a pointer from one record
+/// to another is a single map lookup. Each map keeps its records in the order
+/// they were added, which for parsed data is their order in the file.
 pub struct GedcomData {
     /// Header containing file metadata
     pub header: Header,
-    /// List of submitters of the facts
-    pub submitters: Vec<Submitter>,
+    /// Submitters of the facts
+    pub submitters: IndexMap<Xref, Submitter>,
     /// Individuals within the family tree
-    pub individuals: HashMap<Xref, Individual>,
+    pub individuals: IndexMap<Xref, Individual>,
     /// The family units of the tree, representing relationships between individuals
-    pub families: HashMap<Xref, Family>,
+    pub families: IndexMap<Xref, Family>,
     /// A data repository where `sources` are held
-    pub repositories: Vec<Repository>,
+    pub repositories: IndexMap<Xref, Repository>,
     /// Sources of facts. _ie._ book, document, census, etc.
-    pub sources: Vec<Source>,
+    pub sources: IndexMap<Xref, Source>,
     /// A multimedia asset linked to a fact
-    pub multimedia: Vec<Media>,
+    pub multimedia: IndexMap<Xref, Media>,
+    /// Lines of the parsed file on which each record starts. An xref shared
+    /// by more than one record has more than one line.
+    #[serde(skip)]
+    record_lines: IndexMap<Xref, Vec<u32>>,
+    /// Generated xrefs of the records added with an xref already in use, by
+    /// the xref they were added with
+    #[serde(skip)]
+    duplicate_xrefs: IndexMap<Xref, Vec<Xref>>,
+    /// Generated xrefs of the records that were parsed without one
+    #[serde(skip)]
+    missing_xrefs: Vec<Xref>,
 }
 
-// should maybe store these by xref if available?
 impl GedcomData {
+    /// Starts building a tree programmatically. See `builder::GedcomBuilder`.
+    #[must_use]
+    pub fn builder() -> GedcomBuilder {
+        GedcomBuilder::new()
+    }
+
     /// Adds a `Family` (a relationship between individuals) to the tree
-    pub fn add_family(&mut self, xref: Option<Xref>, family: Family) {
-        
-        if let Some(id) = xref {
-            self.families.insert(id, family);
-        };
-        
+    ///
+    /// A record whose xref is already in use is kept under a generated xref,
+    /// see `duplicates_of`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::MissingXref` if `xref` is `None`
+    ///
+    pub fn add_family(&mut self, xref: Option<Xref>, family: Family) -> Result<(), TreeError> {
+        let xref = self.claim_xref(xref.ok_or(TreeError::MissingXref("Family"))?);
+        self.families.insert(xref, family);
+        Ok(())
     }
 
     /// Adds an `Individual` to the tree
-    pub fn add_individual(&mut self, xref:Option<Xref>, individual: Individual) {
-
-        if let Some(id) = xref {
-                self.individuals.insert(id, individual);
-        };
-        
+    ///
+    /// A record whose xref is already in use is kept under a generated xref,
+    /// see `duplicates_of`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::MissingXref` if `xref` is `None`
+    ///
+    pub fn add_individual(&mut self, xref: Option<Xref>, individual: Individual) -> Result<(), TreeError> {
+        let xref = self.claim_xref(xref.ok_or(TreeError::MissingXref("Individual"))?);
+        self.individuals.insert(xref, individual);
+        Ok(())
     }
 
     /// Adds a data `Repository` to the tree
-    pub fn add_repository(&mut self, repo: Repository) {
-        self.repositories.push(repo);
+    ///
+    /// A record whose xref is already in use is kept under a generated xref,
+    /// see `duplicates_of`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::MissingXref` if the repository has no xref
+    ///
+    pub fn add_repository(&mut self, mut repo: Repository) -> Result<(), TreeError> {
+        let xref = self.claim_xref(repo.xref.clone().ok_or(TreeError::MissingXref("Repository"))?);
+        repo.xref = Some(xref.clone());
+        self.repositories.insert(xref, repo);
+        Ok(())
     }
 
     /// Adds a `Source` to the tree
-    pub fn add_source(&mut self, source: Source) {
-        self.sources.push(source);
+    ///
+    /// A record whose xref is already in use is kept under a generated xref,
+    /// see `duplicates_of`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::MissingXref` if the source has no xref
+    ///
+    pub fn add_source(&mut self, mut source: Source) -> Result<(), TreeError> {
+        let xref = self.claim_xref(source.xref.clone().ok_or(TreeError::MissingXref("Source"))?);
+        source.xref = Some(xref.clone());
+        self.sources.insert(xref, source);
+        Ok(())
     }
 
     /// Adds a `Submitter` to the tree
-    pub fn add_submitter(&mut self, submitter: Submitter) {
-        self.submitters.push(submitter);
+    ///
+    /// A record whose xref is already in use is kept under a generated xref,
+    /// see `duplicates_of`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::MissingXref` if the submitter has no xref
+    ///
+    pub fn add_submitter(&mut self, mut submitter: Submitter) -> Result<(), TreeError> {
+        let xref = self.claim_xref(submitter.xref.clone().ok_or(TreeError::MissingXref("Submitter"))?);
+        submitter.xref = Some(xref.clone());
+        self.submitters.insert(xref, submitter);
+        Ok(())
+    }
+
+    /// Xref to add a record with `xref` under: `xref` itself, or a generated
+    /// one like `@I1-2@` if a record already uses it, so that records sharing
+    /// an xref are all kept
+    fn claim_xref(&mut self, xref: Xref) -> Xref {
+        if !self.contains_xref(xref.as_str()) {
+            return xref;
+        }
+        let mut n = 1;
+        let claimed = loop {
+            n += 1;
+            let candidate = Xref::new(format!("@{}-{}@", xref.id(), n)).expect("generated xref is valid");
+            if !self.contains_xref(candidate.as_str()) && !self.record_lines.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+        // the parser records the line of a record under the xref it starts
+        // with, before adding it
+        if let Some(lines) = self.record_lines.get_mut(&xref) {
+            if lines.len() > 1 {
+                let line = lines.pop();
+                self.record_lines.insert(claimed.clone(), line.into_iter().collect());
+            }
+        }
+        self.duplicate_xrefs.entry(xref).or_default().push(claimed.clone());
+        claimed
+    }
+
+    /// Generated xrefs of the records that were added with `xref` while
+    /// another record used it, in the order they were added. Empty if no
+    /// other record was added with `xref`.
+    #[must_use]
+    pub fn duplicates_of(&self, xref: &str) -> &[Xref] {
+        self.duplicate_xrefs.get(xref).map_or(&[], Vec::as_slice)
+    }
+
+    /// Generates an xref like `@INDI-1@` for a `record_type` record that was
+    /// parsed without one, so that the record is kept and can be reported
+    pub(crate) fn generate_missing_xref(&mut self, record_type: &str) -> Xref {
+        let mut n = 0;
+        let xref = loop {
+            n += 1;
+            let candidate = Xref::new(format!("@{record_type}-{n}@")).expect("generated xref is valid");
+            if !self.contains_xref(candidate.as_str()) && !self.record_lines.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+        self.missing_xrefs.push(xref.clone());
+        xref
+    }
+
+    /// Generated xrefs of the records that were parsed without an xref, in
+    /// the order they were parsed
+    #[must_use]
+    pub fn missing_xrefs(&self) -> &[Xref] {
+        &self.missing_xrefs
+    }
+
+    /// Records that a record with `xref` starts on `line` of the parsed file
+    pub(crate) fn add_record_line(&mut self, xref: &Xref, line: u32) {
+        self.record_lines.entry(xref.clone()).or_default().push(line);
+    }
+
+    /// Forgets the lines of the records with `xref`, once they're removed
+    pub(crate) fn remove_record_lines(&mut self, xref: &str) {
+        self.record_lines.shift_remove(xref);
+        self.duplicate_xrefs.shift_remove(xref);
+        for duplicates in self.duplicate_xrefs.values_mut() {
+            duplicates.retain(|duplicate| duplicate.as_str() != xref);
+        }
+        self.missing_xrefs.retain(|missing| missing.as_str() != xref);
+    }
+
+    /// Moves the lines of the records with `from` over to `to`
+    pub(crate) fn rename_record_lines(&mut self, from: &str, to: &Xref) {
+        if let Some(lines) = self.record_lines.shift_remove(from) {
+            self.record_lines.insert(to.clone(), lines);
+        }
+        let mut renames = IndexMap::new();
+        if let Ok(from) = Xref::new(from) {
+            renames.insert(from, to.clone());
+        }
+        self.remap_duplicate_xrefs(&renames);
+    }
+
+    /// Moves the lines of every record in `renames` over to its new xref
+    pub(crate) fn remap_record_lines(&mut self, renames: &IndexMap<Xref, Xref>) {
+        self.record_lines = std::mem::take(&mut self.record_lines)
+            .into_iter()
+            .map(|(xref, lines)| (renames.get(&xref).cloned().unwrap_or(xref), lines))
+            .collect();
+        self.remap_duplicate_xrefs(renames);
+    }
+
+    fn remap_duplicate_xrefs(&mut self, renames: &IndexMap<Xref, Xref>) {
+        let rename = |xref: Xref| renames.get(&xref).cloned().unwrap_or(xref);
+        self.duplicate_xrefs = std::mem::take(&mut self.duplicate_xrefs)
+            .into_iter()
+            .map(|(xref, duplicates)| (rename(xref), duplicates.into_iter().map(rename).collect()))
+            .collect();
+        self.missing_xrefs = std::mem::take(&mut self.missing_xrefs).into_iter().map(rename).collect();
+    }
+
+    /// Lines of the parsed file on which the records with `xref` start.
+    /// Empty for records that weren't parsed from a file.
+    #[must_use]
+    pub fn record_lines(&self, xref: &str) -> &[u32] {
+        self.record_lines.get(xref).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns true if any record uses `xref`
+    #[must_use]
+    pub fn contains_xref(&self, xref: &str) -> bool {
+        self.submitters.contains_key(xref)
+            || self.individuals.contains_key(xref)
+            || self.families.contains_key(xref)
+            || self.repositories.contains_key(xref)
+            || self.sources.contains_key(xref)
+            || self.multimedia.contains_key(xref)
+    }
+
+    /// Removes an individual along with every pointer to them, dropping
+    /// families left empty. See `edit::remove_individual`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::NotFound` if there is no individual with `xref`
+    ///
+    pub fn remove_individual(&mut self, xref: &str) -> Result<Individual, TreeError> {
+        remove_individual(self, xref)
+    }
+
+    /// Moves a child from family `from` to family `to`, on both sides of the
+    /// link. See `edit::move_child`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::NotFound` if any of the records doesn't exist
+    /// * `TreeError::NotAChild` if `child` isn't a child of `from`
+    ///
+    pub fn move_child(&mut self, child: &str, from: &str, to: &str) -> Result<(), TreeError> {
+        move_child(self, child, from, to)
+    }
+
+    /// Merges the individual `merged` into `survivor`, redirecting every
+    /// pointer to it. See `edit::merge_individuals`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::NotFound` if either individual doesn't exist
+    /// * `TreeError::MergeWithSelf` if `survivor` and `merged` are the same
+    ///
+    pub fn merge_individuals(&mut self,
+                             survivor: &str,
+                             merged: &str,
+                             dry_run: bool) -> Result<IndividualMergeReport, TreeError> {
+        merge_individuals(self, survivor, merged, dry_run)
+    }
+
+    /// Changes the xref of a record and every pointer to it. See `edit::rename_xref`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::NotFound` if there is no record with `from`
+    /// * `TreeError::XrefInUse` if a record already uses `to`
+    ///
+    pub fn rename_xref(&mut self, from: &str, to: Xref) -> Result<(), TreeError> {
+        rename_xref(self, from, to)
+    }
+
+    /// Gives every record a canonical xref, ie. `@I1@`, numbering the
+    /// individuals in `order`. See `edit::renumber_xrefs`.
+    ///
+    /// # Errors
+    ///
+    /// * `TreeError::Cycle` if the tree has a cycle, with `XrefOrder::Topological`
+    /// * `TreeError::DanglingCollision` if a pointer to a missing record uses
+    ///   one of the new xrefs
+    ///
+    pub fn renumber_xrefs(&mut self, order: XrefOrder) -> Result<IndexMap<Xref, Xref>, TreeError> {
+        renumber_xrefs(self, order)
+    }
+
+    /// Imports every record of `other`, renumbering those whose xref is
+    /// already in use. See `merge::merge`.
+    pub fn merge(&mut self, other: GedcomData, options: MergeOptions) -> MergeReport {
+        merge(self, other, options)
+    }
+
+    /// Checks that every pointer refers to an existing record, that links
+    /// between individuals and families are reciprocal, and that no xref is
+    /// used by more than one record. See `validation::validate`.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        validate(self)
+    }
+
+    /// Checks that the dates of births, deaths, burials, marriages and other
+    /// events are possible and plausible. See `chronology::check_chronology`.
+    #[must_use]
+    pub fn check_chronology(&self, options: ChronologyOptions) -> Vec<ChronologyIssue> {
+        check_chronology(self, options)
+    }
+
+    /// Measures missing dates, places, sources, parents and marriages. See
+    /// `completeness::completeness`.
+    #[must_use]
+    pub fn completeness(&self) -> CompletenessReport {
+        completeness(self)
+    }
+
+    /// Adds the missing side of one-sided links between individuals and
+    /// families, returning the added links. See `repair::repair_family_links`.
+    pub fn repair_family_links(&mut self) -> Vec<LinkRepair> {
+        repair_family_links(self)
+    }
+
+    /// Returns the xrefs of all records last changed at or after `since`.
+    /// Records without a parseable `CHAN` date are not included.
+    #[must_use]
+    pub fn changed_since(&self, since: &Timestamp) -> Vec<&Xref> {
+        self.records()
+            .filter(|(_, record)| {
+                record
+                    .change_date()
+                    .and_then(ChangeDate::timestamp)
+                    .is_some_and(|timestamp| &timestamp >= since)
+            })
+            .map(|(xref, _)| xref)
+            .collect()
+    }
+
+    /// Indexes every record by its identifiers. Build the index once to look
+    /// up many records, as each `find_by_*` call scans the whole tree.
+    #[must_use]
+    pub fn identifier_index(&self) -> IdentifierIndex<'_> {
+        let mut index = IdentifierIndex::default();
+        for (xref, record) in self.records() {
+            index.insert(xref, record.identifiers());
+        }
+        index
+    }
+
+    /// Finds the xref of the record with the unique id `uid` (`UID` or `_UID`)
+    #[must_use]
+    pub fn find_by_uid(&self, uid: &str) -> Option<&Xref> {
+        self.find_record(|ids| ids.has_unique_id(uid))
+    }
+
+    /// Finds the xref of the record with the automated record id `rin` (`RIN`)
+    #[must_use]
+    pub fn find_by_rin(&self, rin: &str) -> Option<&Xref> {
+        self.find_record(|ids| ids.automated_record_id.as_deref() == Some(rin))
+    }
+
+    /// Finds the xref of the record with the Ancestral File number `afn` (`AFN`)
+    #[must_use]
+    pub fn find_by_afn(&self, afn: &str) -> Option<&Xref> {
+        self.find_record(|ids| ids.ancestral_file_number.as_deref() == Some(afn))
+    }
+
+    /// Finds the xref of the record with the permanent record file number `rfn` (`RFN`)
+    #[must_use]
+    pub fn find_by_rfn(&self, rfn: &str) -> Option<&Xref> {
+        self.find_record(|ids| ids.permanent_record_file_number.as_deref() == Some(rfn))
+    }
+
+    /// Finds the xrefs of all records with the user reference number `refn` (`REFN`).
+    /// User reference numbers aren't required to be unique.
+    #[must_use]
+    pub fn find_by_user_reference(&self, refn: &str) -> Vec<&Xref> {
+        self.records()
+            .filter(|(_, record)| record.identifiers().has_user_reference(refn))
+            .map(|(xref, _)| xref)
+            .collect()
+    }
+
+    fn find_record(&self, predicate: impl Fn(&Identifiers) -> bool) -> Option<&Xref> {
+        self.records()
+            .find(|(_, record)| predicate(record.identifiers()))
+            .map(|(xref, _)| xref)
+    }
+
+    /// Iterates over every top-level record
+    fn records(&self) -> impl Iterator<Item = (&Xref, &dyn Record)> {
+        let submitters = self.submitters.iter().map(|(xref, s)| (xref, s as &dyn Record));
+        let individuals = self.individuals.iter().map(|(xref, i)| (xref, i as &dyn Record));
+        let families = self.families.iter().map(|(xref, f)| (xref, f as &dyn Record));
+        let repositories = self.repositories.iter().map(|(xref, r)| (xref, r as &dyn Record));
+        let sources = self.sources.iter().map(|(xref, s)| (xref, s as &dyn Record));
+
+        submitters
+            .chain(individuals)
+            .chain(families)
+            .chain(repositories)
+            .chain(sources)
+    }
+
+    /// Counts records, events, genders, lifespans, children, surnames and
+    /// places, listing the `top` surnames and places. See
+    /// `statistics::statistics`.
+    #[must_use]
+    pub fn statistics(&self, top: usize) -> Statistics {
+        statistics(self, top)
     }
 
     /// Outputs a summary of data contained in the tree to stdout
+    ///
+    /// Computes the full `statistics` just to print them, lifespans and name
+    /// and place counts included.
+    #[deprecated(note = "use `statistics` and print the returned `Statistics` instead")]
     pub fn stats(&self) {
-        println!("----------------------");
-        println!("| Gedcom Data Stats: |");
-        println!("----------------------");
-        println!("  submitters: {}", self.submitters.len());
-        println!("  individuals: {}", self.individuals.len());
-        println!("  families: {}", self.families.len());
-        println!("  repositories: {}", self.repositories.len());
-        println!("  sources: {}", self.sources.len());
-        println!("  multimedia: {}", self.multimedia.len());
-        println!("----------------------");
+        print!("{}", self.statistics(0));
     }
 }
 
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// Record of when a record was last modified, the `CHAN` tag
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeDate {
+    /// Date of the change, ie. `1 APR 1998`
+    pub date: Option<String>,
+    /// Time of the change, ie. `12:34:56.789`
+    pub time: Option<String>,
+    /// Notes attached to the change
+    pub notes: Vec<String>,
+}
+
+impl ChangeDate {
+    pub fn add_note(&mut self, note: String) {
+        self.notes.push(note);
+    }
+
+    /// Parses the date and time of the change into a comparable `Timestamp`.
+    /// Returns `None` if there is no date or it isn't an exact date.
+    #[must_use]
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        Timestamp::parse(self.date.as_ref()?, self.time.as_deref())
+    }
+}
+
+/// A point in time parsed from a `CHAN` date and time, ordered chronologically
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Timestamp {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl Timestamp {
+    /// Parses an exact date (`DD MMM YYYY`) and an optional time (`hh:mm[:ss[.fs]]`).
+    #[must_use]
+    pub fn parse(date: &str, time: Option<&str>) -> Option<Timestamp> {
+        let mut parts = date.split_whitespace();
+        let day = parts.next()?.parse::<u8>().ok()?;
+        let month = month_from_abbreviation(parts.next()?)?;
+        let year = parts.next()?.parse::<i32>().ok()?;
+        if parts.next().is_some() || day == 0 || day > 31 {
+            return None;
+        }
+
+        let mut timestamp = Timestamp {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        };
+
+        if let Some(time) = time {
+            let mut parts = time.trim().split(':');
+            timestamp.hour = parts.next()?.parse::<u8>().ok()?;
+            timestamp.minute = parts.next()?.parse::<u8>().ok()?;
+            if let Some(seconds) = parts.next() {
+                // fractions of a second are dropped
+                let whole = seconds.split('.').next()?;
+                timestamp.second = whole.parse::<u8>().ok()?;
+            }
+            if parts.next().is_some()
+                || timestamp.hour > 23
+                || timestamp.minute > 59
+                || timestamp.second > 59
+            {
+                return None;
+            }
+        }
+
+        Some(timestamp)
+    }
+}
+
+/// Converts a GEDCOM month abbreviation (`JAN`..`DEC`) to its number
+#[must_use]
+pub fn month_from_abbreviation(month: &str) -> Option<u8> {
+    let month = match month.to_uppercase().as_str() {
+        "JAN" => 1,
+        "FEB" => 2,
+        "MAR" => 3,
+        "APR" => 4,
+        "MAY" => 5,
+        "JUN" => 6,
+        "JUL" => 7,
+        "AUG" => 8,
+        "SEP" => 9,
+        "OCT" => 10,
+        "NOV" => 11,
+        "DEC" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
@@ -0,0 +1,66 @@
+use crate::types::month_from_abbreviation;
+use serde::{Deserialize, Serialize};
+
+/// Calendar date read from a GEDCOM date value, ie. `1 JAN 1900`, `MAR 1900`
+/// or `ABT 1900`
+///
+/// Only the first date of a range or period is kept (`1900` for
+/// `BET 1900 AND 1910`), marking the date as approximate, and so are dates
+/// with a qualifier like `ABT`, `BEF` or `EST`. Dates are ordered by year,
+/// then month, then day, with missing parts first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DateValue {
+    pub year: i32,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    /// the value was qualified or a range rather than an exact date
+    pub approximate: bool,
+}
+
+impl DateValue {
+    /// Parses a GEDCOM date value, returning `None` for values without a
+    /// recognizable date, ie. `(unknown)`. Calendar escapes like `@#DJULIAN@`
+    /// are ignored.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<DateValue> {
+        let mut approximate = false;
+        let mut parts = Vec::new();
+        for token in value.split_whitespace() {
+            let token = token.to_uppercase();
+            match token.as_str() {
+                "ABT" | "CAL" | "EST" | "BEF" | "AFT" | "BET" | "FROM" | "INT" => approximate = true,
+                "AND" | "TO" => {
+                    approximate = true;
+                    break;
+                }
+                _ if token.starts_with('(') => break,
+                _ if token.starts_with("@#") => (),
+                _ => parts.push(token),
+            }
+        }
+
+        let (day, month, year) = match parts.as_slice() {
+            [year] => (None, None, year),
+            [month, year] => (None, Some(month), year),
+            [day, month, year] => (Some(day), Some(month), year),
+            _ => return None,
+        };
+        // dual dated years, ie. `1699/00`, keep the first year
+        let year = year.split('/').next()?.parse::<i32>().ok()?;
+        let month = match month {
+            Some(month) => Some(month_from_abbreviation(month)?),
+            None => None,
+        };
+        let day = match day {
+            Some(day) => Some(day.parse::<u8>().ok().filter(|day| (1..=31).contains(day))?),
+            None => None,
+        };
+
+        Some(DateValue {
+            year,
+            month,
+            day,
+            approximate,
+        })
+    }
+}
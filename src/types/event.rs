@@ -1,10 +1,10 @@
 use crate::types::SourceCitation;
-use std::{fmt, string::ToString};
+use std::fmt;
 use anyhow::{Result, anyhow};
 use serde::{Serialize, Deserialize};
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EventType {
     Adoption,
     Birth,
@@ -19,9 +19,9 @@ pub enum EventType {
     Other,
 }
 
-impl ToString for EventType {
-    fn to_string(&self) -> String {
-        format!("{self:?}")
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
     }
 }
 
@@ -50,6 +50,12 @@ impl Event {
         self.event = EventType::SourceData(value);
     }
 
+    /// Creates an event from its GEDCOM tag
+    ///
+    /// # Errors
+    ///
+    /// * Unhandled event tag
+    ///
     pub fn from_tag(tag: &str) -> Result<Event> {
         let etype = match tag {
             "ADOP" => EventType::Adoption,
@@ -76,6 +82,7 @@ impl Event {
 }
 
 // clippy doesn't like this
+#[allow(clippy::missing_fields_in_debug)]
 impl std::fmt::Debug for Event {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let event_type = format!("{:?} Event", &self.event);
@@ -90,8 +97,16 @@ impl std::fmt::Debug for Event {
 
 /// Trait given to structs representing entities that have events.
 pub trait HasEvents {
+    /// Adds an event to the entity
+    ///
+    /// # Errors
+    ///
+    /// * The event can't be added to the entity
+    ///
     fn add_event(&mut self, event: Event) -> Result<()>;
     fn events(&self) -> Vec<Event>;
+    /// Mutable access to the events of the entity, ie. to rewrite their citations
+    fn events_mut(&mut self) -> &mut [Event];
     fn dates(&self) -> Vec<String> {
         let mut dates: Vec<String> = Vec::new();
         for event in self.events() {
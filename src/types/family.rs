@@ -1,9 +1,7 @@
-use crate::types::{event::HasEvents, Event};
+use crate::types::{event::HasEvents, ChangeDate, Event, Identifiers, LdsOrdinance, Record, Xref};
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, anyhow};
 
-type Xref = String;
-
 /// Family fact, representing a relationship between `Individual`s
 ///
 /// This data representation understands that HUSB & WIFE are just poorly-named
@@ -14,6 +12,9 @@ pub struct Family {
     pub wives: Vec<Xref>, // mapped from WIFE
     pub children: Vec<Xref>,
     pub num_children: Option<u8>,
+    pub change_date: Option<ChangeDate>,
+    pub identifiers: Identifiers,
+    pub lds_ordinances: Vec<LdsOrdinance>,
     events: Vec<Event>, // why is this privatex}
 }
 
@@ -32,6 +33,19 @@ impl Family {
         self.children.push(xref);
     }
 
+    pub fn add_lds_ordinance(&mut self, ordinance: LdsOrdinance) {
+        self.lds_ordinances.push(ordinance);
+    }
+
+}
+
+impl Record for Family {
+    fn change_date(&self) -> Option<&ChangeDate> {
+        self.change_date.as_ref()
+    }
+    fn identifiers(&self) -> &Identifiers {
+        &self.identifiers
+    }
 }
 
 impl HasEvents for Family {
@@ -48,4 +62,7 @@ impl HasEvents for Family {
     fn events(&self) -> Vec<Event> {
         self.events.clone()
     }
+    fn events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
 }
@@ -0,0 +1,125 @@
+use crate::types::{ChangeDate, Xref};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// User-defined reference number of a record, the `REFN` tag
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UserReference {
+    pub value: String,
+    /// The `TYPE` of the reference number, ie. `Birth certificate`
+    pub reference_type: Option<String>,
+}
+
+/// Identifiers attached to a record in addition to its xref
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Identifiers {
+    /// User reference numbers, the `REFN` tags
+    pub user_references: Vec<UserReference>,
+    /// Automated record id assigned by the source system, the `RIN` tag
+    pub automated_record_id: Option<String>,
+    /// Ancestral File number, the `AFN` tag
+    pub ancestral_file_number: Option<String>,
+    /// Permanent record file number, the `RFN` tag
+    pub permanent_record_file_number: Option<String>,
+    /// Globally unique ids, the `UID` and `_UID` tags
+    pub unique_ids: Vec<String>,
+}
+
+impl Identifiers {
+    pub fn add_user_reference(&mut self, reference: UserReference) {
+        self.user_references.push(reference);
+    }
+
+    pub fn add_unique_id(&mut self, uid: String) {
+        self.unique_ids.push(uid);
+    }
+
+    /// Returns true if the record has `uid` as a unique id. UIDs are compared case-insensitively.
+    #[must_use]
+    pub fn has_unique_id(&self, uid: &str) -> bool {
+        self.unique_ids.iter().any(|id| id.eq_ignore_ascii_case(uid))
+    }
+
+    /// Returns true if the record has a user reference number equal to `value`
+    #[must_use]
+    pub fn has_user_reference(&self, value: &str) -> bool {
+        self.user_references.iter().any(|r| r.value == value)
+    }
+}
+
+/// Xrefs of records by their identifiers, for looking up many records
+/// without scanning the tree each time. When several records share an
+/// identifier, the first one indexed is found, like `GedcomData::find_by_uid`
+/// and the other lookups do.
+#[derive(Clone, Debug, Default)]
+pub struct IdentifierIndex<'t> {
+    /// keyed by the uppercased id, as UIDs compare case-insensitively
+    unique_ids: HashMap<String, &'t Xref>,
+    automated_record_ids: HashMap<&'t str, &'t Xref>,
+    ancestral_file_numbers: HashMap<&'t str, &'t Xref>,
+    permanent_record_file_numbers: HashMap<&'t str, &'t Xref>,
+    user_references: HashMap<&'t str, Vec<&'t Xref>>,
+}
+
+impl<'t> IdentifierIndex<'t> {
+    /// Adds the identifiers of the record `xref`
+    pub fn insert(&mut self, xref: &'t Xref, identifiers: &'t Identifiers) {
+        for uid in &identifiers.unique_ids {
+            self.unique_ids.entry(uid.to_ascii_uppercase()).or_insert(xref);
+        }
+        if let Some(rin) = &identifiers.automated_record_id {
+            self.automated_record_ids.entry(rin).or_insert(xref);
+        }
+        if let Some(afn) = &identifiers.ancestral_file_number {
+            self.ancestral_file_numbers.entry(afn).or_insert(xref);
+        }
+        if let Some(rfn) = &identifiers.permanent_record_file_number {
+            self.permanent_record_file_numbers.entry(rfn).or_insert(xref);
+        }
+        for reference in &identifiers.user_references {
+            let xrefs = self.user_references.entry(&reference.value).or_default();
+            if !xrefs.contains(&xref) {
+                xrefs.push(xref);
+            }
+        }
+    }
+
+    /// The record with the unique id `uid` (`UID` or `_UID`)
+    #[must_use]
+    pub fn uid(&self, uid: &str) -> Option<&'t Xref> {
+        self.unique_ids.get(&uid.to_ascii_uppercase()).copied()
+    }
+
+    /// The record with the automated record id `rin` (`RIN`)
+    #[must_use]
+    pub fn rin(&self, rin: &str) -> Option<&'t Xref> {
+        self.automated_record_ids.get(rin).copied()
+    }
+
+    /// The record with the Ancestral File number `afn` (`AFN`)
+    #[must_use]
+    pub fn afn(&self, afn: &str) -> Option<&'t Xref> {
+        self.ancestral_file_numbers.get(afn).copied()
+    }
+
+    /// The record with the permanent record file number `rfn` (`RFN`)
+    #[must_use]
+    pub fn rfn(&self, rfn: &str) -> Option<&'t Xref> {
+        self.permanent_record_file_numbers.get(rfn).copied()
+    }
+
+    /// The records with the user reference number `refn` (`REFN`), in the
+    /// order they were indexed
+    #[must_use]
+    pub fn user_reference(&self, refn: &str) -> &[&'t Xref] {
+        self.user_references.get(refn).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Trait given to the top-level records of a gedcom file.
+pub trait Record {
+    /// Date the record was last changed
+    fn change_date(&self) -> Option<&ChangeDate>;
+    /// Identifiers of the record other than its xref
+    fn identifiers(&self) -> &Identifiers;
+}
@@ -1,22 +1,27 @@
-use crate::types::{event::HasEvents, CustomData, Event};
+use crate::types::{
+    event::HasEvents, ChangeDate, CustomData, Event, Identifiers, LdsOrdinance, Record,
+    SourceCitation, Xref,
+};
 use anyhow::Result;
 use anyhow::anyhow;
-use std::collections::HashMap;
-use std::collections::HashSet;
+use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 
-type Xref = String;
-
 /// A Person within the family tree
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Individual {
     pub name: Option<Name>,
+    pub alternate_names: Vec<Name>,
     pub title: Option<String>,
     pub sex: Gender,
-    pub fam_spouse: HashSet<Xref>,
-    pub fam_child: HashMap<Xref, Option<Pedigree>>,
+    pub fam_spouse: IndexSet<Xref>,
+    pub fam_child: IndexMap<Xref, FamilyLink>,
+    pub associations: Vec<Association>,
+    pub aliases: Vec<Xref>,
     pub custom_data: Vec<CustomData>,
-    pub last_updated: Option<String>,
+    pub change_date: Option<ChangeDate>,
+    pub identifiers: Identifiers,
+    pub lds_ordinances: Vec<LdsOrdinance>,
     events: Vec<Event>,
 }
 
@@ -24,23 +29,70 @@ impl Individual {
 
     pub fn add_family(&mut self, xref: Xref, link: FamilyLink) {
 
-        match link.0 {
+        match link.link_type {
             FamilyLinkType::Child => {
-                self.fam_child.insert(xref, link.1);
+                self.fam_child.insert(xref, link);
             },
             FamilyLinkType::Spouse => {
                 self.fam_spouse.insert(xref);
             },
-        };
+        }
 
     }
 
+    /// Adds a `NAME`: the first one is the preferred name, later ones are
+    /// kept as alternate names
+    pub fn add_name(&mut self, name: Name) {
+        if self.name.is_none() {
+            self.name = Some(name);
+        } else {
+            self.alternate_names.push(name);
+        }
+    }
+
     pub fn add_custom_data(&mut self, data: CustomData) {
         self.custom_data.push(data);
     }
+
+    pub fn add_lds_ordinance(&mut self, ordinance: LdsOrdinance) {
+        self.lds_ordinances.push(ordinance);
+    }
+
+    pub fn add_association(&mut self, association: Association) {
+        self.associations.push(association);
+    }
+
+    /// Adds a link to another `Individual` record describing the same person, the `ALIA` tag
+    pub fn add_alias(&mut self, xref: Xref) {
+        self.aliases.push(xref);
+    }
+
+    /// Given names and surname of the individual, from the `NAME` value when
+    /// the `GIVN` and `SURN` parts aren't given. `None` without a given name
+    /// or surname.
+    #[must_use]
+    pub fn given_and_surname(&self) -> Option<(String, Option<String>)> {
+        let name = self.name.as_ref()?;
+        let parsed = name.value.as_deref().map(Name::from_value).unwrap_or_default();
+        let given = name.given.clone().or(parsed.given);
+        let surname = name.surname.clone().or(parsed.surname);
+        if given.is_none() && surname.is_none() {
+            return None;
+        }
+        Some((given.unwrap_or_default(), surname))
+    }
     
 }
 
+impl Record for Individual {
+    fn change_date(&self) -> Option<&ChangeDate> {
+        self.change_date.as_ref()
+    }
+    fn identifiers(&self) -> &Identifiers {
+        &self.identifiers
+    }
+}
+
 impl HasEvents for Individual {
     fn add_event(&mut self, event: Event) -> Result<()>{
         self.events.push(event);
@@ -49,10 +101,13 @@ impl HasEvents for Individual {
     fn events(&self) -> Vec<Event> {
         self.events.clone()
     }
+    fn events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
 }
 
 /// Gender of an `Individual`
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Gender {
     Male,
     Female,
@@ -61,24 +116,82 @@ pub enum Gender {
     Unknown,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-enum FamilyLinkType {
+/// Kind of link from an `Individual` to a `Family`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FamilyLinkType {
+    /// `FAMS`, the individual is a spouse in the family
     Spouse,
+    /// `FAMC`, the individual is a child in the family
     Child,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Relationship of a child to the parents of a family, the `PEDI` tag
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Pedigree {
     Adopted,
     Birth,
     Foster,
     Sealing,
+    /// Any value not defined by the specification, kept as found
+    Other(String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FamilyLink(FamilyLinkType, Option<Pedigree>);
+impl Pedigree {
+    /// Converts the value of a `PEDI` tag to a `Pedigree`, ignoring case
+    #[must_use]
+    pub fn from_value(value: &str) -> Pedigree {
+        match value.trim().to_lowercase().as_str() {
+            "adopted" => Pedigree::Adopted,
+            "birth" => Pedigree::Birth,
+            "foster" => Pedigree::Foster,
+            "sealing" => Pedigree::Sealing,
+            _ => Pedigree::Other(value.to_string()),
+        }
+    }
+}
+
+/// Confidence in a child-to-family link, the `STAT` tag
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChildLinkStatus {
+    /// the link is suspected but not proven
+    Challenged,
+    /// the link has been disproven
+    Disproven,
+    /// the link has been proven
+    Proven,
+    /// Any value not defined by the specification, kept as found
+    Other(String),
+}
+
+impl ChildLinkStatus {
+    /// Converts the value of a `STAT` tag to a `ChildLinkStatus`, ignoring case
+    #[must_use]
+    pub fn from_value(value: &str) -> ChildLinkStatus {
+        match value.trim().to_lowercase().as_str() {
+            "challenged" => ChildLinkStatus::Challenged,
+            "disproven" => ChildLinkStatus::Disproven,
+            "proven" => ChildLinkStatus::Proven,
+            _ => ChildLinkStatus::Other(value.to_string()),
+        }
+    }
+}
+
+/// Link from an `Individual` to a `Family`, the `FAMC` and `FAMS` tags
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FamilyLink {
+    link_type: FamilyLinkType,
+    pedigree: Option<Pedigree>,
+    status: Option<ChildLinkStatus>,
+    notes: Vec<String>,
+}
 
 impl FamilyLink {
+    /// Creates a family link from its GEDCOM tag
+    ///
+    /// # Errors
+    ///
+    /// * Unrecognized family link tag
+    ///
     pub fn new(tag: &str) -> Result<FamilyLink> {
         let link_type = match tag {
             "FAMC" => FamilyLinkType::Child,
@@ -86,23 +199,92 @@ impl FamilyLink {
             _ => return Err(anyhow!("Unrecognized family type tag: {}",
                                             tag)),
         };
-        Ok(FamilyLink(link_type, None))
+        Ok(FamilyLink::with_type(link_type))
     }
 
-    pub fn set_pedigree(&mut self, pedigree_text: &str) -> Result<()> {
-        self.1 = match pedigree_text.to_lowercase().as_str() {
-            "adopted" => Some(Pedigree::Adopted),
-            "birth" => Some(Pedigree::Birth),
-            "foster" => Some(Pedigree::Foster),
-            "sealing" => Some(Pedigree::Sealing),
-            _ => return Err(anyhow!("Unrecognized family link pedigree: {}",
-                                    pedigree_text)),
-        };
-        Ok(())
+    /// Creates a family link of the given type, with no pedigree, status or notes
+    #[must_use]
+    pub fn with_type(link_type: FamilyLinkType) -> FamilyLink {
+        FamilyLink {
+            link_type,
+            pedigree: None,
+            status: None,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Sets the pedigree of a child-to-family link. Values not defined by the
+    /// specification are kept as `Pedigree::Other`.
+    pub fn set_pedigree(&mut self, pedigree_text: &str) {
+        self.pedigree = Some(Pedigree::from_value(pedigree_text));
+    }
+
+    pub fn set_status(&mut self, status: ChildLinkStatus) {
+        self.status = Some(status);
+    }
+
+    pub fn add_note(&mut self, note: String) {
+        self.notes.push(note);
+    }
+
+    #[must_use]
+    pub fn link_type(&self) -> FamilyLinkType {
+        self.link_type
+    }
+
+    /// Pedigree of a child-to-family link, `None` if not given
+    #[must_use]
+    pub fn pedigree(&self) -> Option<&Pedigree> {
+        self.pedigree.as_ref()
+    }
+
+    /// Status of a child-to-family link, `None` if not given
+    #[must_use]
+    pub fn status(&self) -> Option<&ChildLinkStatus> {
+        self.status.as_ref()
+    }
+
+    #[must_use]
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+}
+
+/// Link to an associated `Individual`, ie. a godparent or witness, the `ASSO` tag
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Association {
+    /// Reference to the associated individual
+    pub xref: Xref,
+    /// Relation of the associate to this individual (`RELA`), ie. `Godfather`
+    pub relationship: Option<String>,
+    /// Record type of the associate (`TYPE`), used by GEDCOM 5.5
+    pub record_type: Option<String>,
+    pub notes: Vec<String>,
+    pub citations: Vec<SourceCitation>,
+}
+
+impl Association {
+    #[must_use]
+    pub fn new(xref: Xref) -> Association {
+        Association {
+            xref,
+            relationship: None,
+            record_type: None,
+            notes: Vec::new(),
+            citations: Vec::new(),
+        }
+    }
+
+    pub fn add_note(&mut self, note: String) {
+        self.notes.push(note);
+    }
+
+    pub fn add_citation(&mut self, citation: SourceCitation) {
+        self.citations.push(citation);
     }
 }
 
-#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Name {
     pub value: Option<String>,
     pub given: Option<String>,
@@ -112,18 +294,43 @@ pub struct Name {
     pub suffix: Option<String>,
 }
 
+impl Name {
+    /// Creates a name from a `NAME` value, ie. `John /Doe/ Jr.`, splitting
+    /// out the surname given between slashes and the parts around it
+    #[must_use]
+    pub fn from_value(value: &str) -> Name {
+        let mut parts = value.splitn(3, '/').map(str::trim);
+        let non_empty = |part: Option<&str>| part.filter(|p| !p.is_empty()).map(str::to_string);
+        let given = non_empty(parts.next());
+        let surname = non_empty(parts.next());
+        let suffix = non_empty(parts.next());
+        Name {
+            value: Some(value.to_string()),
+            given,
+            surname,
+            suffix,
+            ..Name::default()
+        }
+    }
+}
+
 impl Default for Individual {
 
     fn default() -> Self {
         Individual {
             name: None,
+            alternate_names: Vec::new(),
             title: None,
             sex: Gender::Unknown,
             events: Vec::new(),
-            fam_spouse: HashSet::new(),
-            fam_child: HashMap::new(),
+            fam_spouse: IndexSet::new(),
+            fam_child: IndexMap::new(),
+            associations: Vec::new(),
+            aliases: Vec::new(),
             custom_data: Vec::new(),
-            last_updated: None,
+            change_date: None,
+            identifiers: Identifiers::default(),
+            lds_ordinances: Vec::new(),
         }
     }
     
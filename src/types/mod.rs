@@ -12,7 +12,20 @@ pub use event::{Event, EventType};
 mod address;
 pub use address::*;
 
-type Xref = String;
+mod change_date;
+pub use change_date::*;
+
+mod date;
+pub use date::*;
+
+mod identifier;
+pub use identifier::*;
+
+mod ordinance;
+pub use ordinance::*;
+
+mod xref;
+pub use xref::*;
 
 // top-level record types
 mod header;
@@ -33,7 +46,10 @@ pub use source::*;
 // TODO
 /// Multimedia item
 #[derive(Debug, Serialize, Deserialize)]
-pub struct Media {}
+pub struct Media {
+    /// Optional reference to link to this multimedia item
+    pub xref: Option<Xref>,
+}
 
 /// Data repository, the `REPO` tag
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,10 +60,23 @@ pub struct Repository {
     pub name: Option<String>,
     /// Physical address of the data repository
     pub address: Option<Address>,
+    /// Date the repository record was last changed
+    pub change_date: Option<ChangeDate>,
+    /// Reference numbers and ids of the repository
+    pub identifiers: Identifiers,
+}
+
+impl Record for Repository {
+    fn change_date(&self) -> Option<&ChangeDate> {
+        self.change_date.as_ref()
+    }
+    fn identifiers(&self) -> &Identifiers {
+        &self.identifiers
+    }
 }
 
 /// Citation linking a genealogy fact to a data `Source`
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SourceCitation {
     /// Reference to the `Source`
     pub xref: Xref,
@@ -64,7 +93,7 @@ pub struct RepoCitation {
     pub call_number: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CustomData {
     pub tag: String,
     pub value: String,
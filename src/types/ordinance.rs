@@ -0,0 +1,135 @@
+use crate::types::{SourceCitation, Xref};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Kind of LDS ordinance
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrdinanceType {
+    /// `BAPL`
+    Baptism,
+    /// `CONL`
+    Confirmation,
+    /// `ENDL`
+    Endowment,
+    /// `SLGC`, sealing of a child to its parents
+    ChildSealing,
+    /// `SLGS`, sealing of a husband and wife
+    SpouseSealing,
+}
+
+/// Status of an LDS ordinance, the `STAT` tag
+///
+/// Gedcom 5.5.1 specification, p.52 & p.59
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrdinanceStatus {
+    /// `BIC`, born in the covenant
+    BornInCovenant,
+    /// `CANCELED`, sealing canceled (divorce)
+    Canceled,
+    /// `CHILD`, died before becoming eight years old
+    Child,
+    /// `COMPLETED`, completed but the date is unknown
+    Completed,
+    /// `DNS`, do not seal
+    DoNotSeal,
+    /// `DNS/CAN`, do not seal, previous sealing canceled
+    DoNotSealCanceled,
+    /// `EXCLUDED`, patron excluded this ordinance from being cleared
+    Excluded,
+    /// `INFANT`, died before less than one year old
+    Infant,
+    /// `PRE-1970`, ordinance likely completed, see ordinance index
+    Pre1970,
+    /// `STILLBORN`, born dead so no ordinance is required
+    Stillborn,
+    /// `SUBMITTED`, submitted but not yet cleared
+    Submitted,
+    /// `UNCLEARED`, data for clearing ordinance request was insufficient
+    Uncleared,
+    /// Any value not defined by the specification, kept as found
+    Other(String),
+}
+
+impl OrdinanceStatus {
+    /// Converts the value of a `STAT` tag to an `OrdinanceStatus`, ignoring
+    /// case
+    #[must_use]
+    pub fn from_value(value: &str) -> OrdinanceStatus {
+        match value.trim().to_uppercase().as_str() {
+            "BIC" => OrdinanceStatus::BornInCovenant,
+            "CANCELED" => OrdinanceStatus::Canceled,
+            "CHILD" => OrdinanceStatus::Child,
+            "COMPLETED" => OrdinanceStatus::Completed,
+            "DNS" => OrdinanceStatus::DoNotSeal,
+            "DNS/CAN" => OrdinanceStatus::DoNotSealCanceled,
+            "EXCLUDED" => OrdinanceStatus::Excluded,
+            "INFANT" => OrdinanceStatus::Infant,
+            "PRE-1970" => OrdinanceStatus::Pre1970,
+            "STILLBORN" => OrdinanceStatus::Stillborn,
+            "SUBMITTED" => OrdinanceStatus::Submitted,
+            "UNCLEARED" => OrdinanceStatus::Uncleared,
+            _ => OrdinanceStatus::Other(value.to_string()),
+        }
+    }
+}
+
+/// LDS ordinance of an `Individual` (`BAPL`, `CONL`, `ENDL`, `SLGC`) or a
+/// `Family` (`SLGS`)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LdsOrdinance {
+    pub ordinance: OrdinanceType,
+    pub date: Option<String>,
+    /// Code of the temple where the ordinance was performed (`TEMP`)
+    pub temple: Option<String>,
+    pub place: Option<String>,
+    pub status: Option<OrdinanceStatus>,
+    /// Date the status was set (`STAT.DATE`)
+    pub status_date: Option<String>,
+    /// Family the child is sealed to (`FAMC`), only used by `SLGC`
+    pub family: Option<Xref>,
+    pub notes: Vec<String>,
+    pub citations: Vec<SourceCitation>,
+}
+
+impl LdsOrdinance {
+    #[must_use]
+    pub fn new(ordinance: OrdinanceType) -> LdsOrdinance {
+        LdsOrdinance {
+            ordinance,
+            date: None,
+            temple: None,
+            place: None,
+            status: None,
+            status_date: None,
+            family: None,
+            notes: Vec::new(),
+            citations: Vec::new(),
+        }
+    }
+
+    /// Creates an ordinance from its GEDCOM tag
+    ///
+    /// # Errors
+    ///
+    /// * Unhandled ordinance tag
+    ///
+    pub fn from_tag(tag: &str) -> Result<LdsOrdinance> {
+        let ordinance = match tag {
+            "BAPL" => OrdinanceType::Baptism,
+            "CONL" => OrdinanceType::Confirmation,
+            "ENDL" => OrdinanceType::Endowment,
+            "SLGC" => OrdinanceType::ChildSealing,
+            "SLGS" => OrdinanceType::SpouseSealing,
+            _ => return Err(anyhow!("Unhandled LDS ordinance tag {}", tag)),
+        };
+        Ok(LdsOrdinance::new(ordinance))
+    }
+
+    pub fn add_note(&mut self, note: String) {
+        self.notes.push(note);
+    }
+
+    pub fn add_citation(&mut self, citation: SourceCitation) {
+        self.citations.push(citation);
+    }
+}
@@ -1,20 +1,22 @@
-use crate::types::{Event, RepoCitation};
+use crate::types::{ChangeDate, Event, Identifiers, Record, RepoCitation, Xref};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 /// Source for genealogy facts
 pub struct Source {
-    pub xref: Option<String>,
+    pub xref: Option<Xref>,
     pub data: SourceData,
     pub abbreviation: Option<String>,
     pub title: Option<String>,
+    pub change_date: Option<ChangeDate>,
+    pub identifiers: Identifiers,
     repo_citations: Vec<RepoCitation>,
 }
 
 impl Source {
     #[must_use]
-    pub fn new(xref: Option<String>) -> Source {
+    pub fn new(xref: Option<Xref>) -> Source {
         Source {
             xref,
             data: SourceData {
@@ -23,6 +25,8 @@ impl Source {
             },
             abbreviation: None,
             title: None,
+            change_date: None,
+            identifiers: Identifiers::default(),
             repo_citations: Vec::new(),
         }
     }
@@ -30,6 +34,26 @@ impl Source {
     pub fn add_repo_citation(&mut self, citation: RepoCitation) {
         self.repo_citations.push(citation);
     }
+
+    /// Citations of the repositories holding this source
+    #[must_use]
+    pub fn repo_citations(&self) -> &[RepoCitation] {
+        &self.repo_citations
+    }
+
+    /// Mutable access to the repository citations, ie. to rewrite their pointers
+    pub fn repo_citations_mut(&mut self) -> &mut [RepoCitation] {
+        &mut self.repo_citations
+    }
+}
+
+impl Record for Source {
+    fn change_date(&self) -> Option<&ChangeDate> {
+        self.change_date.as_ref()
+    }
+    fn identifiers(&self) -> &Identifiers {
+        &self.identifiers
+    }
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -40,6 +64,12 @@ pub struct SourceData {
 }
 
 impl SourceData {
+    /// Adds an event recorded by the source
+    ///
+    /// # Errors
+    ///
+    /// * The event can't be added
+    ///
     pub fn add_event(&mut self, event: Event) -> Result<()>{
         self.events.push(event);
         Ok(())
@@ -1,8 +1,6 @@
-use crate::types::Address;
+use crate::types::{Address, ChangeDate, Identifiers, Record, Xref};
 use serde::{Deserialize, Serialize};
 
-type Xref = String;
-
 /// Submitter of the data, ie. who reported the genealogy fact
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Submitter {
@@ -16,6 +14,10 @@ pub struct Submitter {
     pub comments: Option<String>,
     /// Phone number of the submitter
     pub phone: Option<String>,
+    /// Date the submitter record was last changed
+    pub change_date: Option<ChangeDate>,
+    /// Reference numbers and ids of the submitter
+    pub identifiers: Identifiers,
 }
 
 impl Submitter {
@@ -28,6 +30,17 @@ impl Submitter {
             address: None,
             comments: None,
             phone: None,
+            change_date: None,
+            identifiers: Identifiers::default(),
         }
     }
 }
+
+impl Record for Submitter {
+    fn change_date(&self) -> Option<&ChangeDate> {
+        self.change_date.as_ref()
+    }
+    fn identifiers(&self) -> &Identifiers {
+        &self.identifiers
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use std::{borrow::Borrow, convert::TryFrom, fmt, str::FromStr};
+use thiserror::Error;
+
+/// Error for a value that is not a valid cross-reference pointer
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid xref {0}: expected a pointer of the form @ID@")]
+pub struct InvalidXref(pub String);
+
+/// Cross-reference identifier linking records together, ie. `@I1@`
+///
+/// Gedcom 5.5.1 specification, p.24: a pointer is an identifier surrounded by
+/// `@` characters. The identifier itself may not contain `@`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Xref(String);
+
+impl Xref {
+    /// Creates an `Xref`, validating the `@ID@` syntax
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidXref` if `value` is not of the form `@ID@`
+    ///
+    pub fn new(value: impl Into<String>) -> Result<Xref, InvalidXref> {
+        let value = value.into();
+        let is_valid = value.len() > 2
+            && value.starts_with('@')
+            && value.ends_with('@')
+            && !value[1..value.len() - 1].contains('@')
+            && !value.contains(char::is_whitespace);
+        if is_valid {
+            Ok(Xref(value))
+        } else {
+            Err(InvalidXref(value))
+        }
+    }
+
+    /// The pointer including its `@` delimiters
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The identifier between the `@` delimiters, ie. `I1` for `@I1@`
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.0[1..self.0.len() - 1]
+    }
+}
+
+impl fmt::Display for Xref {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Xref {
+    type Err = InvalidXref;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Xref::new(s)
+    }
+}
+
+impl TryFrom<String> for Xref {
+    type Error = InvalidXref;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Xref::new(value)
+    }
+}
+
+impl TryFrom<&str> for Xref {
+    type Error = InvalidXref;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Xref::new(value)
+    }
+}
+
+impl From<Xref> for String {
+    fn from(xref: Xref) -> Self {
+        xref.0
+    }
+}
+
+impl AsRef<str> for Xref {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// lets maps keyed by `Xref` be indexed with a `&str`, ie. `individuals["@I1@"]`
+impl Borrow<str> for Xref {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Xref {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Xref {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Xref {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
@@ -0,0 +1,276 @@
+/*! Referential integrity checks for the `GedcomData` type
+
+ */
+
+use crate::tree::GedcomData;
+use crate::types::{event::HasEvents, Event, Family, Individual, LdsOrdinance, SourceCitation, Xref};
+use serde::Serialize;
+use std::fmt;
+
+/// Kind of problem found by `validate`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum IssueKind {
+    /// A pointer under `tag` refers to a record that doesn't exist
+    DanglingPointer {
+        /// the tag holding the pointer, ie. `FAMC`
+        tag: &'static str,
+        /// the missing record
+        target: Xref,
+    },
+    /// A link under `tag` isn't matched by a link back from the `target` record
+    MissingBackLink {
+        /// the tag holding the link, ie. `CHIL`
+        tag: &'static str,
+        /// the record that should link back
+        target: Xref,
+        /// the tag expected on the `target` record, ie. `FAMC`
+        expected: &'static str,
+    },
+    /// More than one record uses the same xref
+    DuplicateXref {
+        /// the lines on which the records sharing the xref start
+        lines: Vec<u32>,
+        /// the generated xrefs the later records were kept under, see
+        /// `GedcomData::duplicates_of`
+        kept_as: Vec<Xref>,
+    },
+    /// The record was parsed without an xref, and is kept under the generated
+    /// xref it's reported at, see `GedcomData::missing_xrefs`
+    MissingXref,
+}
+
+/// A referential integrity problem, located at the record it was found in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// xref of the record containing the problem
+    pub xref: Xref,
+    /// tag of the record type, ie. `INDI`
+    pub record_type: &'static str,
+    /// line of the parsed file on which the record starts, if known
+    pub line: Option<u32>,
+    /// what is wrong
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        write!(f, "{} {}: ", self.xref, self.record_type)?;
+        match &self.kind {
+            IssueKind::DanglingPointer { tag, target } => {
+                write!(f, "{tag} points to missing record {target}")
+            }
+            IssueKind::MissingBackLink { tag, target, expected } => {
+                write!(f, "{tag} {target} has no matching {expected} link back")
+            }
+            IssueKind::DuplicateXref { lines, kept_as } => {
+                write!(f, "xref is used by {} records (lines {:?}), later ones kept as", kept_as.len() + 1, lines)?;
+                for xref in kept_as {
+                    write!(f, " {xref}")?;
+                }
+                Ok(())
+            }
+            IssueKind::MissingXref => write!(f, "record has no xref, kept under a generated one"),
+        }
+    }
+}
+
+/// Validate the pointers of the `GedcomData`
+///
+/// Reports pointers to records that don't exist, links between individuals
+/// and families that aren't reciprocal (ie. a `CHIL` without a matching
+/// `FAMC`), xrefs used by more than one record and records parsed without an
+/// xref. Issues are listed in record order.
+///
+/// # Arguments
+///
+/// * tree - reference to the `GedcomData`
+///
+#[must_use]
+pub fn validate(tree: &GedcomData) -> Vec<ValidationIssue> {
+    let mut validator = Validator {
+        tree,
+        issues: Vec::new(),
+    };
+
+    validator.check_xrefs();
+    for (xref, indv) in &tree.individuals {
+        validator.check_individual(xref, indv);
+    }
+    for (xref, fam) in &tree.families {
+        validator.check_family(xref, fam);
+    }
+    for (xref, source) in &tree.sources {
+        for citation in source.repo_citations() {
+            if !tree.repositories.contains_key(&citation.xref) {
+                validator.dangling(xref, "SOUR", "REPO", &citation.xref);
+            }
+        }
+    }
+
+    validator.issues
+}
+
+struct Validator<'a> {
+    tree: &'a GedcomData,
+    issues: Vec<ValidationIssue>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, xref: &Xref, record_type: &'static str, kind: IssueKind) {
+        self.issues.push(ValidationIssue {
+            xref: xref.clone(),
+            record_type,
+            line: self.tree.record_lines(xref.as_str()).first().copied(),
+            kind,
+        });
+    }
+
+    fn dangling(&mut self, xref: &Xref, record_type: &'static str, tag: &'static str, target: &Xref) {
+        let kind = IssueKind::DanglingPointer {
+            tag,
+            target: target.clone(),
+        };
+        self.push(xref, record_type, kind);
+    }
+
+    fn missing_back_link(&mut self,
+                         xref: &Xref,
+                         record_type: &'static str,
+                         tag: &'static str,
+                         target: &Xref,
+                         expected: &'static str) {
+        let kind = IssueKind::MissingBackLink {
+            tag,
+            target: target.clone(),
+            expected,
+        };
+        self.push(xref, record_type, kind);
+    }
+
+    fn check_xrefs(&mut self) {
+        let tree = self.tree;
+        let xrefs = tree
+            .submitters
+            .keys()
+            .map(|xref| (xref, "SUBM"))
+            .chain(tree.individuals.keys().map(|xref| (xref, "INDI")))
+            .chain(tree.families.keys().map(|xref| (xref, "FAM")))
+            .chain(tree.repositories.keys().map(|xref| (xref, "REPO")))
+            .chain(tree.sources.keys().map(|xref| (xref, "SOUR")))
+            .chain(tree.multimedia.keys().map(|xref| (xref, "OBJE")));
+
+        for (xref, record_type) in xrefs {
+            if tree.missing_xrefs().contains(xref) {
+                self.push(xref, record_type, IssueKind::MissingXref);
+            }
+            let kept_as = tree.duplicates_of(xref.as_str());
+            if !kept_as.is_empty() {
+                let lines = std::iter::once(xref)
+                    .chain(kept_as)
+                    .flat_map(|xref| tree.record_lines(xref.as_str()))
+                    .copied()
+                    .collect();
+                let kind = IssueKind::DuplicateXref {
+                    lines,
+                    kept_as: kept_as.to_vec(),
+                };
+                self.push(xref, record_type, kind);
+            }
+        }
+    }
+
+    fn check_citations(&mut self, xref: &Xref, record_type: &'static str, citations: &[SourceCitation]) {
+        for citation in citations {
+            if !self.tree.sources.contains_key(&citation.xref) {
+                self.dangling(xref, record_type, "SOUR", &citation.xref);
+            }
+        }
+    }
+
+    fn check_events(&mut self, xref: &Xref, record_type: &'static str, events: &[Event]) {
+        for event in events {
+            self.check_citations(xref, record_type, &event.citations);
+        }
+    }
+
+    fn check_ordinances(&mut self, xref: &Xref, record_type: &'static str, ordinances: &[LdsOrdinance]) {
+        for ordinance in ordinances {
+            if let Some(family) = &ordinance.family {
+                if !self.tree.families.contains_key(family) {
+                    self.dangling(xref, record_type, "FAMC", family);
+                }
+            }
+            self.check_citations(xref, record_type, &ordinance.citations);
+        }
+    }
+
+    fn check_individual(&mut self, xref: &Xref, indv: &Individual) {
+        let tree = self.tree;
+
+        for xref_fam in &indv.fam_spouse {
+            match tree.families.get(xref_fam) {
+                None => self.dangling(xref, "INDI", "FAMS", xref_fam),
+                Some(fam) => {
+                    if !fam.husbs.contains(xref) && !fam.wives.contains(xref) {
+                        self.missing_back_link(xref, "INDI", "FAMS", xref_fam, "HUSB/WIFE");
+                    }
+                }
+            }
+        }
+        for xref_fam in indv.fam_child.keys() {
+            match tree.families.get(xref_fam) {
+                None => self.dangling(xref, "INDI", "FAMC", xref_fam),
+                Some(fam) => {
+                    if !fam.children.contains(xref) {
+                        self.missing_back_link(xref, "INDI", "FAMC", xref_fam, "CHIL");
+                    }
+                }
+            }
+        }
+        for association in &indv.associations {
+            if !tree.individuals.contains_key(&association.xref) {
+                self.dangling(xref, "INDI", "ASSO", &association.xref);
+            }
+            self.check_citations(xref, "INDI", &association.citations);
+        }
+        for alias in &indv.aliases {
+            if !tree.individuals.contains_key(alias) {
+                self.dangling(xref, "INDI", "ALIA", alias);
+            }
+        }
+        self.check_events(xref, "INDI", &indv.events());
+        self.check_ordinances(xref, "INDI", &indv.lds_ordinances);
+    }
+
+    fn check_family(&mut self, xref: &Xref, fam: &Family) {
+        let tree = self.tree;
+
+        for (tag, spouses) in [("HUSB", &fam.husbs), ("WIFE", &fam.wives)] {
+            for xref_spouse in spouses {
+                match tree.individuals.get(xref_spouse) {
+                    None => self.dangling(xref, "FAM", tag, xref_spouse),
+                    Some(spouse) => {
+                        if !spouse.fam_spouse.contains(xref) {
+                            self.missing_back_link(xref, "FAM", tag, xref_spouse, "FAMS");
+                        }
+                    }
+                }
+            }
+        }
+        for xref_child in &fam.children {
+            match tree.individuals.get(xref_child) {
+                None => self.dangling(xref, "FAM", "CHIL", xref_child),
+                Some(child) => {
+                    if !child.fam_child.contains_key(xref) {
+                        self.missing_back_link(xref, "FAM", "CHIL", xref_child, "FAMC");
+                    }
+                }
+            }
+        }
+        self.check_events(xref, "FAM", &fam.events());
+        self.check_ordinances(xref, "FAM", &fam.lds_ordinances);
+    }
+}
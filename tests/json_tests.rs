@@ -1,7 +1,6 @@
 use gedcom::util::parse;
 use gedcom::types::Name;
 use gedcom::Analyzer;
-use serde_json;
 use serde_test::{assert_tokens, Token};
 
 #[test]
@@ -61,6 +60,15 @@ fn serde_entire_gedcom_tree() {
       \"@CHILD@\"
     ],
     \"num_children\": null,
+    \"change_date\": null,
+    \"identifiers\": {
+      \"user_references\": [],
+      \"automated_record_id\": null,
+      \"ancestral_file_number\": null,
+      \"permanent_record_file_number\": null,
+      \"unique_ids\": []
+    },
+    \"lds_ordinances\": [],
     \"events\": [
       {
         \"event\": \"Marriage\",
@@ -85,14 +93,25 @@ fn serde_entire_gedcom_tree() {
     \"surname_prefix\": null,
     \"suffix\": null
   },
+  \"alternate_names\": [],
   \"title\": \"title\",
   \"sex\": \"Male\",
   \"fam_spouse\": [
     \"@FAMILY@\"
   ],
   \"fam_child\": {},
+  \"associations\": [],
+  \"aliases\": [],
   \"custom_data\": [],
-  \"last_updated\": null,
+  \"change_date\": null,
+  \"identifiers\": {
+    \"user_references\": [],
+    \"automated_record_id\": null,
+    \"ancestral_file_number\": null,
+    \"permanent_record_file_number\": null,
+    \"unique_ids\": []
+  },
+  \"lds_ordinances\": [],
   \"events\": [
     {
       \"event\": \"Birth\",
@@ -125,3 +144,26 @@ fn serde_name_counts() {
     )
     }
 }
+
+#[test]
+fn serde_preserves_record_order() {
+    let data = parse("./tests/fixtures/simple.ged").unwrap();
+
+    let xrefs: Vec<&str> = data.individuals.keys().map(|xref| xref.as_str()).collect();
+    assert_eq!(xrefs, vec!["@FATHER@", "@MOTHER@", "@CHILD@"]);
+
+    let analyzer = Analyzer::new(&data).unwrap();
+    let json = analyzer.get_individual_json().unwrap();
+    let father = json.find("\"@FATHER@\"").unwrap();
+    let mother = json.find("\"@MOTHER@\"").unwrap();
+    let child = json.find("\"@CHILD@\"").unwrap();
+    assert!(father < mother && mother < child);
+
+    // output is identical from run to run
+    let washington = parse("./tests/fixtures/washington.ged").unwrap();
+    let again = parse("./tests/fixtures/washington.ged").unwrap();
+    assert_eq!(
+        serde_json::to_string(&washington).unwrap(),
+        serde_json::to_string(&again).unwrap()
+    );
+}
@@ -1,8 +1,35 @@
 use gedcom::util::parse;
-use gedcom::{GedcomData, Analyzer};
+use gedcom::{Analyzer, GedcomData, TreeError};
+use gedcom::builder::GedcomBuilder;
+use gedcom::duplicates::{find_duplicates, DuplicateOptions};
+use gedcom::edit::XrefOrder;
+use gedcom::phonetic::{daitch_mokotoff, phonetic_keys, soundex, Encoding};
+use gedcom::relationship::RelationshipKind;
+use gedcom::completeness::Gap;
+use gedcom::chronology::{ChronologyKind, ChronologyOptions, Severity};
+use gedcom::kinship::{inbreeding_coefficient, KinshipOptions};
+use gedcom::numbering::{ahnentafel, descendant_numbers, Ahnentafel, DescendantScheme, NumberingOptions};
+use gedcom::merge::{MergeAction, MergeOptions};
+use gedcom::analyzer::{
+    ancestors, descendants, EdgeType, GraphOptions, TraversalOptions, TraversalOrder,
+};
+use gedcom::validation::IssueKind;
+use gedcom::parser::Parser;
 use gedcom::types::event::HasEvents;
+use gedcom::types::{
+    ChildLinkStatus, DateValue, EventType, FamilyLinkType, Gender, OrdinanceStatus, OrdinanceType,
+    Pedigree, Timestamp, Xref,
+};
+
+
+/// Parses GEDCOM `records` between a minimal header and the trailer
+fn parse_records(records: &str) -> anyhow::Result<GedcomData> {
+    let gedcom = format!("0 HEAD\n1 GEDC\n2 VERS 5.5\n1 CHAR ASCII\n{}\n0 TRLR", records);
+    Parser::new(gedcom.chars()).parse_record()
+}
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn parses_basic_gedcom() {
     
     let data = parse("./tests/fixtures/simple.ged").unwrap();
@@ -68,7 +95,7 @@ fn parses_basic_gedcom() {
     
     // addresses
     assert_eq!(
-        data.submitters[0]
+        data.submitters["@SUBMITTER@"]
             .address
             .as_ref()
             .unwrap()
@@ -80,7 +107,7 @@ fn parses_basic_gedcom() {
 
     // submitter comments
     assert_eq!(
-        data.submitters[0]
+        data.submitters["@SUBMITTER@"]
             .comments
             .as_ref()
             .unwrap(),
@@ -114,12 +141,1255 @@ fn finds_connected_components() {
     let data = parse("./tests/fixtures/simple.ged").unwrap();
     
     if let Ok(analyzer) = Analyzer::new(&data) {
-        let child = String::from("@CHILD@");
-        let father = String::from("@FATHER@");
-        let mother = String::from("@MOTHER@");
-        assert!(analyzer.components[0].contains(&child));
-        assert!(analyzer.components[0].contains(&mother));
-        assert!(analyzer.components[0].contains(&father));
+        let child = Xref::new("@CHILD@").unwrap();
+        let father = Xref::new("@FATHER@").unwrap();
+        let mother = Xref::new("@MOTHER@").unwrap();
+        assert!(analyzer.components[0].contains(&&child));
+        assert!(analyzer.components[0].contains(&&mother));
+        assert!(analyzer.components[0].contains(&&father));
     }
 }
 
+
+#[test]
+fn parses_change_dates() {
+    let records = "\
+0 @I1@ INDI
+1 NAME /Changed/
+1 CHAN
+2 DATE 1 APR 1998
+3 TIME 12:34:56.789
+2 NOTE A note
+3 CONT continued
+0 @I2@ INDI
+1 NAME /Unchanged/
+0 @F1@ FAM
+1 HUSB @I1@
+1 CHAN
+2 DATE 19 JUN 2000
+0 @S1@ SOUR
+1 TITL Title
+1 CHAN
+2 DATE 2 JAN 1990
+0 @R1@ REPO
+1 NAME Repository
+1 CHAN
+2 DATE 1 APR 1998
+3 TIME 12:34";
+
+    let data = parse_records(records).unwrap();
+
+    let change_date = data.individuals["@I1@"].change_date.as_ref().unwrap();
+    assert_eq!(change_date.date.as_deref(), Some("1 APR 1998"));
+    assert_eq!(change_date.time.as_deref(), Some("12:34:56.789"));
+    assert_eq!(change_date.notes, vec!["A note\ncontinued".to_string()]);
+    assert_eq!(
+        change_date.timestamp(),
+        Some(Timestamp { year: 1998, month: 4, day: 1, hour: 12, minute: 34, second: 56 })
+    );
+    assert!(data.individuals["@I2@"].change_date.is_none());
+    assert!(data.families["@F1@"].change_date.is_some());
+
+    let since = Timestamp::parse("1 APR 1998", Some("12:34")).unwrap();
+    let mut changed = data.changed_since(&since);
+    changed.sort();
+    assert_eq!(changed, vec!["@F1@", "@I1@", "@R1@"]);
+}
+
+#[test]
+fn parses_record_identifiers() {
+    let records = "\
+0 @I1@ INDI
+1 NAME /Identified/
+1 REFN 1234
+2 TYPE Birth certificate
+1 REFN 5678
+1 RIN 42
+1 AFN 1FF4-3DB
+1 RFN SUBM-1:77
+1 _UID 0B6D1A5C3E2F4B1D8A9E7C6B5A4D3E2F
+0 @F1@ FAM
+1 HUSB @I1@
+1 UID 6B8C2E1A-5D4F-4E3B-9A2C-1F0E9D8C7B6A
+1 REFN 5678
+0 @S1@ SOUR
+1 TITL Title
+1 RIN 7";
+
+    let data = parse_records(records).unwrap();
+
+    let ids = &data.individuals["@I1@"].identifiers;
+    assert_eq!(ids.user_references.len(), 2);
+    assert_eq!(ids.user_references[0].value, "1234");
+    assert_eq!(ids.user_references[0].reference_type.as_deref(), Some("Birth certificate"));
+    assert_eq!(ids.automated_record_id.as_deref(), Some("42"));
+    assert_eq!(ids.ancestral_file_number.as_deref(), Some("1FF4-3DB"));
+    assert_eq!(ids.permanent_record_file_number.as_deref(), Some("SUBM-1:77"));
+    assert!(data.individuals["@I1@"].custom_data.is_empty());
+
+    assert_eq!(
+        data.find_by_uid("0b6d1a5c3e2f4b1d8a9e7c6b5a4d3e2f").map(Xref::as_str),
+        Some("@I1@")
+    );
+    assert_eq!(
+        data.find_by_uid("6B8C2E1A-5D4F-4E3B-9A2C-1F0E9D8C7B6A").map(Xref::as_str),
+        Some("@F1@")
+    );
+    assert_eq!(data.find_by_rin("7").map(Xref::as_str), Some("@S1@"));
+    assert_eq!(data.find_by_afn("1FF4-3DB").map(Xref::as_str), Some("@I1@"));
+    assert_eq!(data.find_by_rfn("SUBM-1:77").map(Xref::as_str), Some("@I1@"));
+    assert!(data.find_by_uid("unknown").is_none());
+
+    let mut refs = data.find_by_user_reference("5678");
+    refs.sort();
+    assert_eq!(refs, vec!["@F1@", "@I1@"]);
+
+    let index = data.identifier_index();
+    assert_eq!(index.uid("0B6D1A5C3E2F4B1D8A9E7C6B5A4D3E2F").map(Xref::as_str), Some("@I1@"));
+    assert_eq!(index.rin("7").map(Xref::as_str), Some("@S1@"));
+    assert_eq!(index.afn("1FF4-3DB").map(Xref::as_str), Some("@I1@"));
+    assert_eq!(index.rfn("SUBM-1:77").map(Xref::as_str), Some("@I1@"));
+    assert!(index.uid("unknown").is_none());
+    assert_eq!(index.user_reference("5678"), &["@I1@", "@F1@"]);
+}
+
+#[test]
+fn parses_associations_and_aliases() {
+    let records = "\
+0 @CHILD@ INDI
+1 NAME /Child/
+1 ASSO @GODFATHER@
+2 RELA Godfather
+2 NOTE Named at the baptism
+2 SOUR @S1@
+3 PAGE 12
+1 ALIA @ALIAS@
+0 @GODFATHER@ INDI
+1 NAME /Godfather/
+0 @ALIAS@ INDI
+1 NAME /Child alias/";
+
+    let data = parse_records(records).unwrap();
+
+    let child = &data.individuals["@CHILD@"];
+    assert_eq!(child.associations.len(), 1);
+    assert_eq!(child.associations[0].xref, "@GODFATHER@");
+    assert_eq!(child.associations[0].relationship.as_deref(), Some("Godfather"));
+    assert_eq!(child.associations[0].notes, vec!["Named at the baptism".to_string()]);
+    assert_eq!(child.associations[0].citations[0].page.as_deref(), Some("12"));
+    assert_eq!(child.aliases, vec!["@ALIAS@".to_string()]);
+
+    // associations and aliases are only followed when requested
+    let analyzer = Analyzer::new(&data).unwrap();
+    assert_eq!(analyzer.components.len(), 3);
+
+    let options = GraphOptions {
+        include_associations: true,
+        include_aliases: true,
+        ..Default::default()
+    };
+    let analyzer = Analyzer::with_options(&data, options).unwrap();
+    assert_eq!(analyzer.components.len(), 1);
+
+    let godfather = Xref::new("@GODFATHER@").unwrap();
+    assert_eq!(
+        analyzer.linked_individuals(&godfather),
+        vec![(&Xref::new("@CHILD@").unwrap(), EdgeType::Association)]
+    );
+}
+
+#[test]
+fn parses_lds_ordinances() {
+    let records = "\
+0 @I1@ INDI
+1 NAME /Member/
+1 BAPL
+2 DATE 5 MAY 1900
+2 TEMP SLAKE
+2 STAT completed
+3 DATE 6 MAY 1900
+1 ENDL
+2 STAT PRE-1970
+1 SLGC
+2 FAMC @F1@
+2 STAT BIC
+0 @F1@ FAM
+1 CHIL @I1@
+1 SLGS
+2 STAT DNS/CAN
+2 PLAC Salt Lake City
+2 NOTE Sealing canceled";
+
+    let data = parse_records(records).unwrap();
+
+    let ordinances = &data.individuals["@I1@"].lds_ordinances;
+    assert_eq!(ordinances.len(), 3);
+    assert_eq!(ordinances[0].ordinance, OrdinanceType::Baptism);
+    assert_eq!(ordinances[0].date.as_deref(), Some("5 MAY 1900"));
+    assert_eq!(ordinances[0].temple.as_deref(), Some("SLAKE"));
+    assert_eq!(ordinances[0].status, Some(OrdinanceStatus::Completed));
+    assert_eq!(ordinances[0].status_date.as_deref(), Some("6 MAY 1900"));
+    assert_eq!(ordinances[1].status, Some(OrdinanceStatus::Pre1970));
+    assert_eq!(ordinances[2].ordinance, OrdinanceType::ChildSealing);
+    assert_eq!(ordinances[2].family.as_ref().map(Xref::as_str), Some("@F1@"));
+    assert_eq!(ordinances[2].status, Some(OrdinanceStatus::BornInCovenant));
+
+    let sealing = &data.families["@F1@"].lds_ordinances[0];
+    assert_eq!(sealing.ordinance, OrdinanceType::SpouseSealing);
+    assert_eq!(sealing.status, Some(OrdinanceStatus::DoNotSealCanceled));
+    assert_eq!(sealing.place.as_deref(), Some("Salt Lake City"));
+    assert_eq!(sealing.notes, vec!["Sealing canceled".to_string()]);
+
+    // statuses outside the specification are kept rather than rejected
+    let data = parse_records(&records.replace("STAT BIC", "STAT UNKNOWN")).unwrap();
+    assert_eq!(
+        data.individuals["@I1@"].lds_ordinances[2].status,
+        Some(OrdinanceStatus::Other("UNKNOWN".to_string()))
+    );
+}
+
+#[test]
+fn parses_child_family_links() {
+    let records = "\
+0 @I1@ INDI
+1 NAME /Child/
+1 FAMC @F1@
+2 PEDI ADOPTED
+2 STAT Proven
+2 NOTE Adoption papers
+3 CONT found in 1950
+1 FAMC @F2@
+2 PEDI step
+2 STAT challenged
+1 FAMC @F3@
+1 FAMC @F4@
+2 STAT Rumored";
+
+    let data = parse_records(records).unwrap();
+    let child = &data.individuals["@I1@"];
+
+    let adopted = &child.fam_child["@F1@"];
+    assert_eq!(adopted.link_type(), FamilyLinkType::Child);
+    assert_eq!(adopted.pedigree(), Some(&Pedigree::Adopted));
+    assert_eq!(adopted.status(), Some(&ChildLinkStatus::Proven));
+    assert_eq!(adopted.notes(), ["Adoption papers\nfound in 1950".to_string()]);
+
+    let step = &child.fam_child["@F2@"];
+    assert_eq!(step.pedigree(), Some(&Pedigree::Other("step".into())));
+    assert_eq!(step.status(), Some(&ChildLinkStatus::Challenged));
+
+    let unspecified = &child.fam_child["@F3@"];
+    assert!(unspecified.pedigree().is_none());
+    assert!(unspecified.status().is_none());
+
+    // statuses outside the specification are kept rather than rejected
+    let rumored = &child.fam_child["@F4@"];
+    assert_eq!(rumored.status(), Some(&ChildLinkStatus::Other("Rumored".into())));
+}
+
+#[test]
+fn stores_records_by_xref() {
+    let data = parse("./tests/fixtures/sample.ged").unwrap();
+
+    // citation -> source -> repository
+    let source = &data.sources["@S1@"];
+    let repo_xref = &source.repo_citations()[0].xref;
+    assert!(data.repositories.contains_key(repo_xref));
+    assert!(data.submitters.contains_key("@U1@"));
+
+    let xref = Xref::new("@I1@").unwrap();
+    assert_eq!(xref.id(), "I1");
+    assert_eq!(xref.to_string(), "@I1@");
+    assert!(Xref::new("I1").is_err());
+    assert!(Xref::new("@@").is_err());
+    assert!(Xref::new("@I@1@").is_err());
+
+    // records without an xref are kept under a generated one and reported
+    let records = "\
+0 @I1@ INDI
+1 NAME /Somebody/
+0 INDI
+1 NAME /Nobody/";
+    let data = parse_records(records).unwrap();
+    assert_eq!(data.individuals.len(), 2);
+    assert_eq!(data.missing_xrefs(), [Xref::new("@INDI-1@").unwrap()]);
+    let issues = data.validate();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].xref.as_str(), "@INDI-1@");
+    assert_eq!(issues[0].kind, IssueKind::MissingXref);
+    assert_eq!(issues[0].to_string(), "line 7: @INDI-1@ INDI: record has no xref, kept under a generated one");
+
+    let records = "\
+0 @F1@ FAM
+1 HUSB I1";
+    assert!(parse_records(records).is_err());
+}
+
+#[test]
+fn validates_pointers() {
+    let data = parse("./tests/fixtures/simple.ged").unwrap();
+    assert!(data.validate().is_empty());
+
+    let records = "\
+0 @I1@ INDI
+1 NAME /Father/
+1 FAMS @F1@
+0 @I2@ INDI
+1 NAME /Child/
+1 FAMC @F9@
+1 BIRT
+2 SOUR @S9@
+0 @F1@ FAM
+1 HUSB @I1@
+1 WIFE @I3@
+1 CHIL @I2@
+0 @I1@ INDI
+1 NAME /Duplicate/
+1 FAMS @F1@";
+
+    let data = parse_records(records).unwrap();
+    let issues = data.validate();
+    let i1 = Xref::new("@I1@").unwrap();
+
+    // both records sharing @I1@ are kept, the later one under a new xref
+    let i1_2 = Xref::new("@I1-2@").unwrap();
+    assert_eq!(data.individuals[&i1].name.as_ref().unwrap().value.as_deref(), Some("/Father/"));
+    assert_eq!(data.individuals[&i1_2].name.as_ref().unwrap().value.as_deref(), Some("/Duplicate/"));
+    assert_eq!(data.duplicates_of("@I1@"), std::slice::from_ref(&i1_2));
+    assert_eq!(data.record_lines("@I1-2@"), [17]);
+
+    assert_eq!(issues.len(), 6);
+    assert_eq!(issues[0].xref, i1);
+    assert_eq!(issues[0].kind, IssueKind::DuplicateXref { lines: vec![5, 17], kept_as: vec![i1_2] });
+    assert_eq!(
+        issues[0].to_string(),
+        "line 5: @I1@ INDI: xref is used by 2 records (lines [5, 17]), later ones kept as @I1-2@"
+    );
+    assert_eq!(issues[1].line, Some(8));
+    assert_eq!(
+        issues[1].kind,
+        IssueKind::DanglingPointer { tag: "FAMC", target: Xref::new("@F9@").unwrap() }
+    );
+    assert_eq!(issues[2].to_string(), "line 8: @I2@ INDI: SOUR points to missing record @S9@");
+    assert_eq!(issues[3].to_string(), "line 17: @I1-2@ INDI: FAMS @F1@ has no matching HUSB/WIFE link back");
+    assert_eq!(issues[4].to_string(), "line 13: @F1@ FAM: WIFE points to missing record @I3@");
+    assert_eq!(
+        issues[5].kind,
+        IssueKind::MissingBackLink {
+            tag: "CHIL",
+            target: Xref::new("@I2@").unwrap(),
+            expected: "FAMC",
+        }
+    );
+}
+
+#[test]
+fn repairs_family_links() {
+    let records = "\
+0 @I1@ INDI
+1 NAME /Father/
+1 SEX M
+0 @I2@ INDI
+1 NAME /Mother/
+1 FAMS @F1@
+0 @I3@ INDI
+1 NAME /Child/
+1 FAMC @F1@
+0 @I4@ INDI
+1 NAME /Child/
+0 @F1@ FAM
+1 HUSB @I1@
+1 CHIL @I4@";
+
+    let mut data = parse_records(records).unwrap();
+    assert_eq!(data.validate().len(), 4);
+
+    let repairs: Vec<String> = data
+        .repair_family_links()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        repairs,
+        vec![
+            "added WIFE @I2@ to @F1@",
+            "added CHIL @I3@ to @F1@",
+            "added FAMS @F1@ to @I1@",
+            "added FAMC @F1@ to @I4@",
+        ]
+    );
+    assert!(data.validate().is_empty());
+    assert_eq!(data.individuals["@I4@"].fam_child["@F1@"].link_type(), FamilyLinkType::Child);
+    assert_eq!(data.families["@F1@"].children, vec!["@I4@", "@I3@"]);
+
+    assert!(data.repair_family_links().is_empty());
+
+    // spouses of unknown sex take the roles still empty, one each
+    let records = "\
+0 @I1@ INDI
+1 NAME /First/
+1 FAMS @F1@
+0 @I2@ INDI
+1 NAME /Second/
+1 FAMS @F1@
+0 @I3@ INDI
+1 NAME /Third/
+1 FAMS @F1@
+0 @F1@ FAM";
+
+    let mut data = parse_records(records).unwrap();
+    let repairs: Vec<String> = data
+        .repair_family_links()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(repairs, vec!["added HUSB @I1@ to @F1@", "added WIFE @I2@ to @F1@"]);
+    assert_eq!(data.families["@F1@"].husbs, vec!["@I1@"]);
+    assert_eq!(data.families["@F1@"].wives, vec!["@I2@"]);
+
+    // links listed twice are repaired once
+    let records = "\
+0 @I1@ INDI
+1 NAME /Spouse/
+1 FAMS @F1@
+1 FAMS @F1@
+0 @I2@ INDI
+1 NAME /Child/
+0 @F1@ FAM
+1 CHIL @I2@
+1 CHIL @I2@";
+
+    let mut data = parse_records(records).unwrap();
+    let repairs: Vec<String> = data
+        .repair_family_links()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(repairs, vec!["added HUSB @I1@ to @F1@", "added FAMC @F1@ to @I2@"]);
+    assert_eq!(data.families["@F1@"].husbs, vec!["@I1@"]);
+    assert!(data.families["@F1@"].wives.is_empty());
+    assert_eq!(data.individuals["@I2@"].fam_child.len(), 1);
+}
+
+#[test]
+fn edits_records_keeping_links() {
+    let records = "\
+0 @I1@ INDI
+1 NAME /Father/
+1 SEX M
+1 FAMS @F1@
+0 @I2@ INDI
+1 NAME /Mother/
+1 SEX F
+1 FAMS @F1@
+0 @I3@ INDI
+1 NAME /Child/
+1 FAMC @F1@
+2 PEDI adopted
+1 ASSO @I1@
+2 RELA Godfather
+1 BIRT
+2 SOUR @S1@
+0 @I4@ INDI
+1 NAME /Stepfather/
+1 FAMS @F2@
+0 @F1@ FAM
+1 HUSB @I1@
+1 WIFE @I2@
+1 CHIL @I3@
+0 @F2@ FAM
+1 HUSB @I4@
+0 @S1@ SOUR
+1 TITL Parish register";
+
+    let mut data = parse_records(records).unwrap();
+
+    // move a child, keeping the link details
+    data.move_child("@I3@", "@F1@", "@F2@").unwrap();
+    assert!(data.families["@F1@"].children.is_empty());
+    assert_eq!(data.families["@F2@"].children, vec!["@I3@"]);
+    assert_eq!(data.individuals["@I3@"].fam_child["@F2@"].pedigree(), Some(&Pedigree::Adopted));
+    assert!(data.validate().is_empty());
+    assert!(matches!(
+        data.move_child("@I3@", "@F1@", "@F2@"),
+        Err(TreeError::NotAChild { .. })
+    ));
+
+    // rename records and every pointer to them
+    data.rename_xref("@I1@", Xref::new("@I10@").unwrap()).unwrap();
+    data.rename_xref("@S1@", Xref::new("@S2@").unwrap()).unwrap();
+    assert_eq!(data.individuals.keys().next().unwrap(), "@I10@");
+    assert_eq!(data.families["@F1@"].husbs, vec!["@I10@"]);
+    assert_eq!(data.individuals["@I3@"].associations[0].xref, "@I10@");
+    assert_eq!(data.individuals["@I3@"].events()[0].citations[0].xref, "@S2@");
+    assert_eq!(data.sources["@S2@"].xref.as_ref().unwrap(), "@S2@");
+    assert!(data.validate().is_empty());
+    assert!(matches!(
+        data.rename_xref("@I2@", Xref::new("@I3@").unwrap()),
+        Err(TreeError::XrefInUse(_))
+    ));
+
+    // remove individuals, dropping families left empty
+    data.remove_individual("@I4@").unwrap();
+    assert!(data.families["@F2@"].husbs.is_empty());
+    let child = data.remove_individual("@I3@").unwrap();
+    assert_eq!(child.name.unwrap().value.unwrap(), "/Child/");
+    assert!(!data.families.contains_key("@F2@"));
+    assert_eq!(data.families.len(), 1);
+    data.remove_individual("@I10@").unwrap();
+    assert_eq!(data.families["@F1@"].wives, vec!["@I2@"]);
+    assert!(data.validate().is_empty());
+    assert!(matches!(data.remove_individual("@I4@"), Err(TreeError::NotFound(_))));
+}
+
+#[test]
+fn builds_trees() {
+    let data = GedcomData::builder()
+        .family("doe").husband("john").wife("jane").child("jim").marriage("5 JUN 1925", "")
+        .individual("john").name("John /Doe/ Jr.").sex(Gender::Male).birth("1 JAN 1900", "Boston")
+        .individual("jane").name("Jane /Roe/").death("", "Salem")
+        .individual("jim").name("Jim /Doe/")
+        .build()
+        .unwrap();
+
+    assert!(data.validate().is_empty());
+    assert_eq!(data.individuals.keys().collect::<Vec<_>>(), vec!["@I1@", "@I2@", "@I3@"]);
+    let fam = &data.families["@F1@"];
+    assert_eq!(fam.husbs, vec!["@I1@"]);
+    assert_eq!(fam.wives, vec!["@I2@"]);
+    assert_eq!(fam.events()[0].date.as_deref(), Some("5 JUN 1925"));
+    assert_eq!(fam.events()[0].place, None);
+
+    let john = &data.individuals["@I1@"];
+    let name = john.name.as_ref().unwrap();
+    assert_eq!(name.given.as_deref(), Some("John"));
+    assert_eq!(name.surname.as_deref(), Some("Doe"));
+    assert_eq!(name.suffix.as_deref(), Some("Jr."));
+    assert_eq!(john.events()[0].place.as_deref(), Some("Boston"));
+    assert!(john.fam_spouse.contains("@F1@"));
+    assert_eq!(data.individuals["@I3@"].fam_child["@F1@"].link_type(), FamilyLinkType::Child);
+
+    // new records get xrefs that aren't used yet
+    let builder = GedcomBuilder::from(data).individual("jill").name("Jill /Doe/");
+    assert_eq!(builder.xref(), "@I4@");
+    let data = builder.family("doe2").husband("jim").build();
+    assert!(matches!(data, Err(TreeError::UnknownLabel(label)) if label == "jim"));
+
+    let data = GedcomData::builder()
+        .family("f").marriage("1900", "").marriage("1901", "")
+        .build();
+    assert!(matches!(data, Err(TreeError::DuplicateEvent { .. })));
+}
+
+#[test]
+fn merges_trees() {
+    let mut data = parse("./tests/fixtures/sample.ged").unwrap();
+    let other = parse("./tests/fixtures/sample.ged").unwrap();
+    let individuals = data.individuals.len();
+    let families = data.families.len();
+
+    let options = MergeOptions { unify_sources: true, unify_repositories: true };
+    let report = data.merge(other, options);
+
+    assert!(data.validate().is_empty());
+    assert_eq!(data.individuals.len(), individuals * 2);
+    assert_eq!(data.families.len(), families * 2);
+    assert_eq!(data.sources.len(), 1);
+    assert_eq!(data.repositories.len(), 1);
+    assert_eq!(report.imported, 1 + individuals + families);
+
+    let changes: Vec<String> = report.changes.iter().map(ToString::to_string).collect();
+    assert_eq!(changes[0], "REPO @R1@ unified with @R1@");
+    assert_eq!(changes[1], "SOUR @S1@ unified with @S1@");
+    assert_eq!(changes[2], "SUBM @U1@ renumbered to @U2@");
+    assert_eq!(changes.len(), 3 + individuals + families);
+
+    // imported families point at the renumbered individuals
+    let renamed = |original: &str| {
+        report.changes.iter().find(|change| change.original == original).unwrap().xref.clone()
+    };
+    let fam = &data.families[&renamed("@F1@")];
+    assert_eq!(fam.husbs, vec![renamed("@I1@")]);
+
+    // without unification the sources are renumbered as well
+    let mut data = parse("./tests/fixtures/sample.ged").unwrap();
+    let report = data.merge(parse("./tests/fixtures/sample.ged").unwrap(), MergeOptions::default());
+    assert_eq!(data.sources.len(), 2);
+    assert!(report.changes.iter().all(|change| change.action == MergeAction::Renumbered));
+    assert!(data.validate().is_empty());
+}
+
+#[test]
+fn renumbers_xrefs() {
+    let mut data = parse("./tests/fixtures/simple.ged").unwrap();
+    let renames = data.renumber_xrefs(XrefOrder::FileOrder).unwrap();
+    assert_eq!(renames["@FATHER@"], "@I1@");
+    assert_eq!(renames["@CHILD@"], "@I3@");
+    assert_eq!(renames["@FAMILY@"], "@F1@");
+    assert_eq!(renames["@SUBMITTER@"], "@U1@");
+    assert_eq!(data.header.submitter_tag.as_deref(), Some("@U1@"));
+    assert_eq!(data.submitters["@U1@"].xref.as_ref().unwrap(), "@U1@");
+    assert_eq!(data.families["@F1@"].children, vec!["@I3@"]);
+    assert!(data.individuals["@I3@"].fam_child.contains_key("@F1@"));
+    assert_eq!(data.record_lines("@I1@"), &[15]);
+    assert!(data.validate().is_empty());
+
+    // surname order: Child, Father, Mother
+    data.renumber_xrefs(XrefOrder::Surname).unwrap();
+    assert_eq!(data.individuals.keys().collect::<Vec<_>>(), vec!["@I1@", "@I2@", "@I3@"]);
+    assert_eq!(data.record_lines("@I1@"), &[36]);
+    assert_eq!(data.families["@F1@"].children, vec!["@I1@"]);
+    assert!(data.validate().is_empty());
+
+    // parents before their child
+    data.renumber_xrefs(XrefOrder::Topological).unwrap();
+    assert_eq!(data.record_lines("@I3@"), &[36]);
+    assert_eq!(data.families["@F1@"].children, vec!["@I3@"]);
+
+    // the same tree numbered the same way gives the same xrefs
+    let mut other = parse("./tests/fixtures/sample.ged").unwrap();
+    let mut data = parse("./tests/fixtures/sample.ged").unwrap();
+    data.rename_xref("@I1@", Xref::new("@PERSON1@").unwrap()).unwrap();
+    data.renumber_xrefs(XrefOrder::FileOrder).unwrap();
+    other.renumber_xrefs(XrefOrder::FileOrder).unwrap();
+    assert_eq!(serde_json::to_string(&data).unwrap(), serde_json::to_string(&other).unwrap());
+
+    // a pointer to a missing record can't take over a new xref
+    let records = "\
+0 @A@ INDI
+1 NAME /Child/
+1 FAMC @F1@
+0 @B@ FAM";
+    let mut data = parse_records(records).unwrap();
+    let err = data.renumber_xrefs(XrefOrder::FileOrder).unwrap_err();
+    assert_eq!(err.to_string(), "pointer to missing record @F1@ would point to a renumbered record");
+    assert!(data.individuals.contains_key("@A@"));
+}
+
+#[test]
+fn parses_date_values() {
+    let date = DateValue::parse("2 Oct 1822").unwrap();
+    assert_eq!((date.year, date.month, date.day, date.approximate), (1822, Some(10), Some(2), false));
+    let date = DateValue::parse("FROM Jan 1820 TO DEC 1825").unwrap();
+    assert_eq!((date.year, date.month, date.day, date.approximate), (1820, Some(1), None, true));
+    assert_eq!(DateValue::parse("BEF 1828").unwrap().year, 1828);
+    assert_eq!(DateValue::parse("@#DJULIAN@ 11 FEB 1699/00").unwrap().year, 1699);
+    assert!(DateValue::parse("(unknown)").is_none());
+    assert!(DateValue::parse("32 JAN 1900").is_none());
+    assert!(DateValue::parse("1 JAN 1900") < DateValue::parse("FEB 1900"));
+}
+
+#[test]
+fn computes_soundex_codes() {
+    assert_eq!(soundex("Robert").unwrap(), "R163");
+    assert_eq!(soundex("Rupert").unwrap(), "R163");
+    assert_eq!(soundex("Ashcraft").unwrap(), "A261");
+    assert_eq!(soundex("Tymczak").unwrap(), "T522");
+    assert_eq!(soundex("Pfister").unwrap(), "P236");
+    assert_eq!(soundex("Müller").unwrap(), soundex("Muller").unwrap());
+    assert!(soundex("123").is_none());
+}
+
+#[test]
+fn finds_duplicate_individuals() {
+    let mut data = parse("./tests/fixtures/sample.ged").unwrap();
+    data.merge(parse("./tests/fixtures/sample.ged").unwrap(), MergeOptions::default());
+
+    let analyzer = Analyzer::new(&data).unwrap();
+    let duplicates = analyzer.find_duplicates(DuplicateOptions::default());
+    assert_eq!(duplicates, find_duplicates(&data, DuplicateOptions::default()));
+
+    // every individual is paired with its copy, and nothing else
+    assert_eq!(duplicates.len(), 3);
+    let robert = &duplicates[0];
+    assert_eq!(robert.first, "@I1@");
+    assert_eq!(robert.second, "@I4@");
+    assert_eq!(robert.breakdown.name, 30);
+    assert_eq!(robert.breakdown.surname_sound, 10);
+    assert_eq!(robert.breakdown.birth, 25);
+    assert_eq!(robert.breakdown.death, 25);
+    assert_eq!(robert.breakdown.relatives, 10);
+    assert_eq!(robert.score, robert.breakdown.total());
+    assert!(!robert.same_component);
+    assert!(duplicates.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+    // different given names, or different sexes, count against a pair
+    let data = GedcomData::builder()
+        .individual("a").name("John /Smith/").sex(Gender::Male).birth("1900", "")
+        .individual("b").name("Jon /Smyth/").sex(Gender::Male).birth("1900", "")
+        .individual("c").name("Mary /Smith/").sex(Gender::Female).birth("1900", "")
+        .build()
+        .unwrap();
+    let duplicates = find_duplicates(&data, DuplicateOptions { min_score: -100 });
+    assert_eq!(duplicates[0].first, "@I1@");
+    assert_eq!(duplicates[0].second, "@I2@");
+    assert_eq!(duplicates[0].breakdown.name, 0);
+    assert_eq!(duplicates[0].breakdown.surname_sound, 10);
+    assert_eq!(duplicates[2].breakdown.name, -20);
+    assert_eq!(duplicates[2].breakdown.sex, -30);
+}
+
+#[test]
+fn merges_duplicate_individuals() {
+    let records = "\
+0 @I1@ INDI
+1 NAME John /Doe/
+1 SEX M
+1 BIRT
+2 DATE 1 JAN 1900
+2 SOUR @S1@
+1 FAMS @F1@
+1 ALIA @I2@
+1 CHAN
+2 DATE 1 JAN 2020
+0 @I2@ INDI
+1 NAME J /Doe/
+1 BIRT
+2 DATE 1 JAN 1900
+2 SOUR @S2@
+1 DEAT
+2 DATE 1970
+1 FAMC @F2@
+1 _UID 0123456789ABCDEF
+1 _MILT Navy
+1 CHAN
+2 DATE 2 FEB 2021
+0 @I3@ INDI
+1 NAME Jack /Smith/
+1 ASSO @I2@
+2 RELA Friend
+0 @F1@ FAM
+1 HUSB @I1@
+0 @F2@ FAM
+1 CHIL @I2@
+0 @S1@ SOUR
+1 TITL Parish register
+0 @S2@ SOUR
+1 TITL Census";
+
+    let mut data = parse_records(records).unwrap();
+
+    let report = data.merge_individuals("@I1@", "@I2@", true).unwrap();
+    let changes: Vec<String> = report.changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        changes,
+        vec![
+            "added alternate name J /Doe/",
+            "added citation of @S2@ to Birth event",
+            "added Death event",
+            "added custom data _MILT",
+            "added identifier 0123456789ABCDEF",
+            "updated change date to 2 FEB 2021",
+            "redirected CHIL of @F2@",
+            "redirected ASSO of @I3@",
+        ]
+    );
+    // a dry run leaves the tree untouched
+    assert!(data.individuals.contains_key("@I2@"));
+    assert_eq!(data.individuals["@I1@"].events().len(), 1);
+
+    let merged = data.merge_individuals("@I1@", "@I2@", false).unwrap();
+    assert_eq!(merged.changes, report.changes);
+    assert!(!merged.dry_run);
+    assert!(!data.individuals.contains_key("@I2@"));
+    assert!(data.validate().is_empty());
+
+    let john = &data.individuals["@I1@"];
+    assert_eq!(john.name.as_ref().unwrap().value.as_deref(), Some("John /Doe/"));
+    assert_eq!(john.alternate_names[0].value.as_deref(), Some("J /Doe/"));
+    assert_eq!(john.change_date.as_ref().unwrap().date.as_deref(), Some("2 FEB 2021"));
+    assert_eq!(john.events().len(), 2);
+    assert_eq!(john.events()[0].citations.len(), 2);
+    assert!(john.aliases.is_empty());
+    assert!(john.fam_child.contains_key("@F2@"));
+    assert!(john.identifiers.has_unique_id("0123456789abcdef"));
+    assert_eq!(data.families["@F2@"].children, vec!["@I1@"]);
+    assert_eq!(data.individuals["@I3@"].associations[0].xref, "@I1@");
+
+    assert!(matches!(
+        data.merge_individuals("@I1@", "@I1@", true),
+        Err(TreeError::MergeWithSelf(_))
+    ));
+}
+
+#[test]
+fn traverses_ancestors_and_descendants() {
+    let mut data = GedcomData::builder()
+        .individual("gpa").name("Grandpa /Doe/")
+        .individual("gma").name("Grandma /Doe/")
+        .individual("dad").name("Dad /Doe/")
+        .individual("mom").name("Mom /Roe/")
+        .individual("kid").name("Kid /Doe/")
+        .individual("adoptee").name("Adoptee /Doe/")
+        .family("f1").husband("gpa").wife("gma").child("dad")
+        .family("f2").husband("dad").wife("mom").child("kid").child("adoptee")
+        .build()
+        .unwrap();
+    data.individuals["@I6@"].fam_child["@F2@"].set_pedigree("adopted");
+    let xrefs = |lineage: Vec<(&Xref, usize, Vec<&Xref>)>| {
+        lineage.iter().map(|(xref, generation, _)| format!("{xref}:{generation}")).collect::<Vec<_>>()
+    };
+
+    let analyzer = Analyzer::new(&data).unwrap();
+    let kid = Xref::new("@I5@").unwrap();
+    let lineage: Vec<_> = analyzer.ancestors(&kid, TraversalOptions::default()).collect();
+    assert_eq!(xrefs(lineage.clone()), vec!["@I3@:1", "@I4@:1", "@I1@:2", "@I2@:2"]);
+    assert_eq!(lineage[2].2, vec!["@I5@", "@I3@", "@I1@"]);
+
+    let depth_first = TraversalOptions { order: TraversalOrder::DepthFirst, ..Default::default() };
+    let lineage = ancestors(&data, &kid, depth_first).collect();
+    assert_eq!(xrefs(lineage), vec!["@I3@:1", "@I1@:2", "@I2@:2", "@I4@:1"]);
+
+    let parents_only = TraversalOptions { max_depth: Some(1), ..Default::default() };
+    assert_eq!(ancestors(&data, &kid, parents_only).count(), 2);
+
+    let gpa = Xref::new("@I1@").unwrap();
+    let lineage = descendants(&data, &gpa, TraversalOptions::default()).collect();
+    assert_eq!(xrefs(lineage), vec!["@I3@:1", "@I5@:2", "@I6@:2"]);
+
+    let birth_only = TraversalOptions { birth_only: true, ..Default::default() };
+    let lineage = descendants(&data, &gpa, birth_only).collect();
+    assert_eq!(xrefs(lineage), vec!["@I3@:1", "@I5@:2"]);
+    let adoptee = Xref::new("@I6@").unwrap();
+    assert_eq!(ancestors(&data, &adoptee, birth_only).count(), 0);
+    assert_eq!(ancestors(&data, &adoptee, TraversalOptions::default()).count(), 4);
+}
+
+#[test]
+fn describes_relationships() {
+    let data = GedcomData::builder()
+        .individual("gpa").name("Grandpa /Doe/").sex(Gender::Male)
+        .individual("gma").name("Grandma /Roe/").sex(Gender::Female)
+        .individual("gma2").name("Second /Poe/").sex(Gender::Female)
+        .individual("dad").name("Dad /Doe/").sex(Gender::Male)
+        .individual("aunt").name("Aunt /Doe/").sex(Gender::Female)
+        .individual("half-uncle").name("Half /Doe/").sex(Gender::Male)
+        .individual("mom").name("Mom /Moe/").sex(Gender::Female)
+        .individual("me").name("Me /Doe/").sex(Gender::Male)
+        .individual("bro").name("Bro /Doe/").sex(Gender::Male)
+        .individual("step-mom").name("Step /Loe/").sex(Gender::Female)
+        .individual("half-sis").name("Sis /Doe/").sex(Gender::Female)
+        .individual("uncle").name("Uncle /Koe/").sex(Gender::Male)
+        .individual("cousin").name("Cousin /Koe/").sex(Gender::Female)
+        .individual("cousin-kid").name("Kid /Joe/").sex(Gender::Male)
+        .individual("cousin-husband").name("Husband /Joe/").sex(Gender::Male)
+        .individual("bro-wife").name("Wife /Hoe/").sex(Gender::Female)
+        .individual("step-mom-ex").name("Ex /Loe/").sex(Gender::Male)
+        .individual("step-bro").name("Step /Loe/").sex(Gender::Male)
+        .family("f1").husband("gpa").wife("gma").child("dad").child("aunt")
+        .family("f2").husband("gpa").wife("gma2").child("half-uncle")
+        .family("f3").husband("dad").wife("mom").child("me").child("bro")
+        .family("f4").husband("dad").wife("step-mom").child("half-sis")
+        .family("f5").husband("uncle").wife("aunt").child("cousin")
+        .family("f6").husband("cousin-husband").wife("cousin").child("cousin-kid")
+        .family("f7").husband("bro").wife("bro-wife")
+        .family("f8").husband("step-mom-ex").wife("step-mom").child("step-bro")
+        .build()
+        .unwrap();
+    let analyzer = Analyzer::new(&data).unwrap();
+    let describe = |from: usize, to: usize| {
+        let (from, to) = (Xref::new(format!("@I{from}@")).unwrap(), Xref::new(format!("@I{to}@")).unwrap());
+        analyzer.relationship(&from, &to).map(|relationship| relationship.to_string())
+    };
+
+    assert_eq!(describe(8, 8).as_deref(), Some("self"));
+    assert_eq!(describe(8, 1).as_deref(), Some("grandfather"));
+    assert_eq!(describe(8, 4).as_deref(), Some("father"));
+    assert_eq!(describe(4, 8).as_deref(), Some("son"));
+    assert_eq!(describe(8, 9).as_deref(), Some("brother"));
+    assert_eq!(describe(8, 11).as_deref(), Some("half-sister"));
+    assert_eq!(describe(8, 5).as_deref(), Some("aunt"));
+    assert_eq!(describe(8, 6).as_deref(), Some("half-uncle"));
+    assert_eq!(describe(8, 13).as_deref(), Some("1st cousin"));
+    assert_eq!(describe(8, 14).as_deref(), Some("1st cousin once removed"));
+    assert_eq!(describe(14, 8).as_deref(), Some("1st cousin once removed"));
+    assert_eq!(describe(1, 14).as_deref(), Some("great-grandson"));
+    assert_eq!(describe(14, 6).as_deref(), Some("half-granduncle"));
+    assert_eq!(describe(8, 10).as_deref(), Some("step-mother"));
+    assert_eq!(describe(8, 18).as_deref(), Some("step-brother"));
+    assert_eq!(describe(10, 8).as_deref(), Some("step-son"));
+    assert_eq!(describe(4, 7).as_deref(), Some("wife"));
+    assert_eq!(describe(8, 16).as_deref(), Some("sister-in-law"));
+    assert_eq!(describe(16, 8).as_deref(), Some("brother-in-law"));
+    assert_eq!(describe(7, 1).as_deref(), Some("father-in-law"));
+    assert_eq!(describe(8, 12).as_deref(), Some("husband of aunt"));
+    assert_eq!(describe(18, 15), None);
+
+    let brother = analyzer.relationship(&Xref::new("@I8@").unwrap(), &Xref::new("@I9@").unwrap()).unwrap();
+    assert_eq!(brother.common_ancestors, vec!["@I4@", "@I7@"]);
+    assert_eq!(brother.kind, RelationshipKind::Blood { up: 1, down: 1, half: false });
+}
+
+#[test]
+fn numbers_ancestors_and_descendants() {
+    let data = GedcomData::builder()
+        .individual("gpa").name("Grandpa /Doe/")
+        .individual("gma").name("Grandma /Doe/")
+        .individual("son").name("Son /Doe/")
+        .individual("daughter").name("Daughter /Doe/")
+        .individual("son-wife").name("Wife /Roe/")
+        .individual("daughter-husband").name("Husband /Poe/")
+        .individual("grandson").name("Grandson /Doe/")
+        .individual("granddaughter").name("Granddaughter /Poe/")
+        .individual("cousins-child").name("Child /Doe/")
+        .family("f1").husband("gpa").wife("gma").child("son").child("daughter")
+        .family("f2").husband("son").wife("son-wife").child("grandson")
+        .family("f3").husband("daughter-husband").wife("daughter").child("granddaughter")
+        .family("f4").husband("grandson").wife("granddaughter").child("cousins-child")
+        .build()
+        .unwrap();
+
+    let root = Xref::new("@I9@").unwrap();
+    let ancestors = ahnentafel(&data, &root, NumberingOptions::default()).unwrap();
+    assert_eq!(ancestors.numbers.len(), 11);
+    assert_eq!(ancestors.numbers[&1], "@I9@");
+    assert_eq!(ancestors.numbers[&5], "@I5@");
+    assert_eq!(ancestors.numbers[&7], "@I4@");
+    assert_eq!(ancestors.numbers_of(&Xref::new("@I1@").unwrap()), vec![8, 14]);
+    assert_eq!(Ahnentafel::generation(14), 3);
+    let parents_only = NumberingOptions { max_generations: Some(1), ..Default::default() };
+    assert_eq!(ahnentafel(&data, &root, parents_only).unwrap().numbers.len(), 3);
+
+    let gpa = Xref::new("@I1@").unwrap();
+    let d_aboville = descendant_numbers(&data, &gpa, DescendantScheme::DAboville, NumberingOptions::default());
+    let d_aboville = d_aboville.unwrap();
+    assert_eq!(
+        d_aboville.numbers.keys().collect::<Vec<_>>(),
+        vec!["1", "1.1", "1.1.1", "1.1.1.1", "1.2", "1.2.1", "1.2.1.1"]
+    );
+    assert_eq!(d_aboville.numbers_of(&root), vec!["1.1.1.1", "1.2.1.1"]);
+    let henry = descendant_numbers(&data, &gpa, DescendantScheme::Henry, NumberingOptions::default());
+    assert_eq!(henry.unwrap().numbers_of(&root), vec!["1111", "1211"]);
+}
+
+#[test]
+fn analyzes_pedigree_collapse() {
+    let data = parse("./tests/fixtures/royal92.ged").unwrap();
+    let analyzer = Analyzer::new(&data).unwrap();
+    let charles = Xref::new("@I58@").unwrap();
+    let options = NumberingOptions { max_generations: Some(6), ..Default::default() };
+    let report = analyzer.implex(&charles, options).unwrap();
+
+    assert_eq!(report.generations.len(), 7);
+    assert_eq!(report.generations[4].repeated(), 0);
+    let fifth = report.generations[5];
+    assert_eq!((fifth.slots, fifth.filled, fifth.distinct), (32, 32, 30));
+    assert_eq!(fifth.to_string(), "generation 5: 32 slots, 32 filled, 30 distinct, 2 repeated");
+
+    // Christian IX and Queen Victoria descend through both parents
+    assert_eq!(report.repeated[0].xref, "@I225@");
+    assert_eq!(report.repeated[0].numbers, vec![16, 50]);
+    let victoria = report.repeated.iter().find(|ancestor| ancestor.xref == "@I1@").unwrap();
+    assert_eq!(victoria.numbers, vec![47, 49]);
+}
+
+#[test]
+fn computes_kinship_coefficients() {
+    let data = GedcomData::builder()
+        .individual("gpa").name("Grandpa /Doe/")
+        .individual("gma").name("Grandma /Doe/")
+        .individual("son").name("Son /Doe/")
+        .individual("daughter").name("Daughter /Doe/")
+        .individual("son-wife").name("Wife /Roe/")
+        .individual("daughter-husband").name("Husband /Poe/")
+        .individual("grandson").name("Grandson /Doe/")
+        .individual("granddaughter").name("Granddaughter /Poe/")
+        .individual("cousins-child").name("Child /Doe/")
+        .individual("gma2").name("Second /Koe/")
+        .individual("half-son").name("Half /Doe/")
+        .family("f1").husband("gpa").wife("gma").child("son").child("daughter")
+        .family("f2").husband("son").wife("son-wife").child("grandson")
+        .family("f3").husband("daughter-husband").wife("daughter").child("granddaughter")
+        .family("f4").husband("grandson").wife("granddaughter").child("cousins-child")
+        .family("f5").husband("gpa").wife("gma2").child("half-son")
+        .build()
+        .unwrap();
+    let analyzer = Analyzer::new(&data).unwrap();
+    let options = KinshipOptions::default();
+    let r = |a: &str, b: &str| {
+        let (a, b) = (Xref::new(a).unwrap(), Xref::new(b).unwrap());
+        analyzer.relationship_coefficient(&a, &b, options).unwrap().value
+    };
+
+    assert_eq!(r("@I3@", "@I3@"), 1.0);
+    assert_eq!(r("@I1@", "@I3@"), 0.5);
+    assert_eq!(r("@I3@", "@I4@"), 0.5);
+    assert_eq!(r("@I3@", "@I11@"), 0.25);
+    assert_eq!(r("@I7@", "@I8@"), 0.125);
+    assert_eq!(r("@I5@", "@I6@"), 0.0);
+
+    let cousins_child = Xref::new("@I9@").unwrap();
+    let inbreeding = analyzer.inbreeding_coefficient(&cousins_child, options).unwrap();
+    assert_eq!(inbreeding.value, 0.0625);
+    assert_eq!(inbreeding.ancestors.len(), 2);
+    assert_eq!(inbreeding.ancestors[0].paths, 1);
+    assert_eq!(inbreeding.ancestors[0].contribution, 0.03125);
+    // the child of first cousins is related to each parent a little more
+    assert_eq!(r("@I9@", "@I7@"), 0.5625 / 1.0625_f64.sqrt());
+
+    let royal = parse("./tests/fixtures/royal92.ged").unwrap();
+    let charles = Xref::new("@I58@").unwrap();
+    let inbreeding = inbreeding_coefficient(&royal, &charles, options).unwrap();
+    let victoria = inbreeding.ancestors.iter().find(|ancestor| ancestor.xref == "@I1@").unwrap();
+    assert_eq!(victoria.contribution, 1.0 / 512.0);
+    assert!(inbreeding.value > 0.01);
+
+    // a cycle is reported rather than counted, however deep it is
+    let looped = GedcomData::builder()
+        .individual("a").name("A /Doe/")
+        .individual("b").name("B /Roe/")
+        .individual("c").name("C /Doe/")
+        .family("f1").husband("a").wife("b").child("c")
+        .family("f2").husband("c").child("a")
+        .build()
+        .unwrap();
+    let options = KinshipOptions { max_generations: Some(1), ..options };
+    let error = inbreeding_coefficient(&looped, &Xref::new("@I3@").unwrap(), options).unwrap_err();
+    assert_eq!(error.to_string(), "tree has a cycle: @I3@ -> @F2@ -> @I1@ -> @F1@ -> @I3@");
+    let missing = Xref::new("@I9@").unwrap();
+    assert!(matches!(inbreeding_coefficient(&looped, &missing, options), Err(TreeError::NotFound(_))));
+}
+
+#[test]
+fn reports_and_breaks_cycles() {
+    let data = GedcomData::builder()
+        .individual("a").name("A /Doe/")
+        .individual("b").name("B /Doe/")
+        .individual("c").name("C /Doe/")
+        .family("f1").husband("a").child("b")
+        .family("f2").husband("b").child("c")
+        .family("f3").husband("c").child("a")
+        .build()
+        .unwrap();
+
+    let error = Analyzer::new(&data).err().unwrap();
+    let Some(TreeError::Cycle(cycle)) = error.downcast_ref::<TreeError>() else {
+        panic!("expected a cycle, got {}", error);
+    };
+    assert_eq!(cycle.links.len(), 3);
+    assert_eq!(cycle.links[0].parent, "@I1@");
+    assert_eq!(cycle.links[2].child, "@I1@");
+    assert_eq!(error.to_string(), "tree has a cycle: @I1@ -> @F1@ -> @I2@ -> @F2@ -> @I3@ -> @F3@ -> @I1@");
+
+    let options = GraphOptions { break_cycles: true, ..Default::default() };
+    let analyzer = Analyzer::with_options(&data, options).unwrap();
+    assert_eq!(analyzer.individuals_sorted, vec!["@I3@", "@I2@", "@I1@"]);
+    assert_eq!(analyzer.excluded_links.len(), 1);
+    assert_eq!(analyzer.excluded_links[0].family, "@F3@");
+    assert!(Analyzer::new(&parse("./tests/fixtures/royal92.ged").unwrap()).unwrap().excluded_links.is_empty());
+}
+
+#[test]
+fn checks_chronology() {
+    let records = "\
+0 @I1@ INDI
+1 NAME Father /Doe/
+1 SEX M
+1 BIRT
+2 DATE 1 JAN 1800
+1 DEAT
+2 DATE 10 MAR 1850
+1 BURI
+2 DATE 1 MAR 1850
+1 RESI
+2 DATE 1851
+0 @I2@ INDI
+1 NAME Mother /Doe/
+1 SEX F
+1 BIRT
+2 DATE 1790
+1 DEAT
+2 DATE ABT 1960
+0 @I3@ INDI
+1 NAME Late /Doe/
+1 BIRT
+2 DATE 1 JAN 1851
+0 @I4@ INDI
+1 NAME Old /Doe/
+1 BIRT
+2 DATE 1700
+1 DEAT
+2 DATE 1830
+0 @F1@ FAM
+1 HUSB @I1@
+1 WIFE @I2@
+1 CHIL @I3@
+1 CHIL @I4@";
+
+    let data = parse_records(records).unwrap();
+    let issues = data.check_chronology(ChronologyOptions::default());
+    let found: Vec<_> = issues.iter().map(|issue| (issue.xref.as_str(), issue.severity, &issue.kind)).collect();
+
+    let father = Xref::new("@I1@").unwrap();
+    let (late, old) = (Xref::new("@I3@").unwrap(), Xref::new("@I4@").unwrap());
+    assert_eq!(found, vec![
+        ("@I1@", Severity::Error, &ChronologyKind::BurialBeforeDeath),
+        ("@I1@", Severity::Error, &ChronologyKind::EventAfterDeath {
+            event: EventType::Residence,
+            deceased: father.clone(),
+        }),
+        ("@I4@", Severity::Warning, &ChronologyKind::LongLifespan { years: 129 }),
+        ("@F1@", Severity::Error, &ChronologyKind::BornAfterFatherDeath {
+            child: late.clone(),
+            father: father.clone(),
+        }),
+        ("@F1@", Severity::Warning, &ChronologyKind::BornAfterMotherAge {
+            child: late,
+            mother: Xref::new("@I2@").unwrap(),
+            age: 60,
+        }),
+        ("@F1@", Severity::Error, &ChronologyKind::BornBeforeParent {
+            child: old.clone(),
+            parent: father,
+        }),
+        ("@F1@", Severity::Error, &ChronologyKind::BornBeforeParent {
+            child: old,
+            parent: Xref::new("@I2@").unwrap(),
+        }),
+    ]);
+
+    // a marriage before a spouse was born is an error, not an early marriage
+    let records = "\
+0 @I1@ INDI
+1 NAME Groom /Doe/
+1 BIRT
+2 DATE 1 JAN 1800
+0 @F1@ FAM
+1 HUSB @I1@
+1 MARR
+2 DATE 1790";
+    let issues = parse_records(records).unwrap().check_chronology(ChronologyOptions::default());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].to_string(), "line 9: @F1@ FAM: error: married before the birth of @I1@");
+
+    let royal = parse("./tests/fixtures/royal92.ged").unwrap();
+    let issues = royal.check_chronology(ChronologyOptions::default());
+    assert_eq!(issues[0].to_string(), "line 22905: @I2948@ INDI: error: born after death");
+    let early = issues.iter().find(|issue| issue.xref == "@F336@").unwrap();
+    assert_eq!(early.to_string(), "line 25466: @F336@ FAM: warning: @I1370@ married at 9");
+}
+
+#[test]
+fn reports_research_gaps() {
+    let data = parse("./tests/fixtures/simple.ged").unwrap();
+    let report = data.completeness();
+    assert_eq!(report.summary.individuals, 3);
+    assert_eq!(report.summary.brick_walls, 2);
+    assert_eq!(report.summary.missing_birth_dates, 0);
+    assert_eq!((report.summary.events, report.summary.unsourced_events), (7, 7));
+    assert_eq!(report.families[0].gaps, vec![Gap::Source(EventType::Marriage)]);
+
+    let data = GedcomData::builder()
+        .individual("dad").name("Dad /Doe/").birth("1 JAN 1900", "Boston")
+        .individual("kid").name("Kid /Doe/").death("1990", "")
+        .family("f1").husband("dad").child("kid")
+        .build()
+        .unwrap();
+    let report = data.completeness();
+    assert_eq!(report.individuals[0].gaps, vec![
+        Gap::DeathDate,
+        Gap::DeathPlace,
+        Gap::Parents,
+        Gap::Source(EventType::Birth),
+    ]);
+    assert_eq!(report.individuals[1].gaps[..2], [Gap::BirthDate, Gap::BirthPlace]);
+    assert_eq!(report.families[0].gaps, vec![Gap::Marriage]);
+    assert_eq!(report.summary.missing_marriages, 1);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["summary"]["brick_walls"], 1);
+    assert_eq!(json["individuals"][0]["gaps"][2], "Parents");
+
+    // a family without spouses is a gap of the family, not of its children
+    let data = GedcomData::builder()
+        .individual("kid").name("Kid /Doe/")
+        .family("f1").child("kid")
+        .build()
+        .unwrap();
+    let report = data.completeness();
+    assert_eq!(report.summary.brick_walls, 0);
+    assert_eq!(report.summary.missing_spouses, 1);
+    assert_eq!(report.families[0].gaps, vec![Gap::Spouses, Gap::Marriage]);
+}
+
+#[test]
+fn computes_statistics() {
+    let data = parse("./tests/fixtures/simple.ged").unwrap();
+    let stats = data.statistics(10);
+    assert_eq!(stats.records.submitters, 1);
+    assert_eq!(stats.records.individuals, 3);
+    assert_eq!(stats.records.families, 1);
+    assert_eq!(stats.average_lifespan, Some(77.0));
+    assert_eq!(stats.events["Birth"], 3);
+    assert_eq!(
+        stats.to_string(),
+        "----------------------\n\
+         | Gedcom Data Stats: |\n\
+         ----------------------\n  \
+         submitters: 1\n  \
+         individuals: 3\n  \
+         families: 1\n  \
+         repositories: 0\n  \
+         sources: 0\n  \
+         multimedia: 0\n\
+         ----------------------\n"
+    );
+
+    let data = GedcomData::builder()
+        .individual("dad").name("John /Doe/").sex(Gender::Male).birth("1900", "Boston").death("1970", "Boston")
+        .individual("mom").name("Mary /Roe/").sex(Gender::Female).birth("1905", "Salem")
+        .individual("kid").name("Jim /Doe/").birth("1930", "Boston")
+        .family("f1").husband("dad").wife("mom").child("kid").marriage("1929", "Salem")
+        .family("f2").husband("dad")
+        .build()
+        .unwrap();
+    let stats = data.statistics(1);
+    assert_eq!((stats.genders.male, stats.genders.female, stats.genders.unknown), (1, 1, 1));
+    assert_eq!((stats.first_year, stats.last_year), (Some(1900), Some(1970)));
+    assert_eq!(stats.average_lifespan, Some(70.0));
+    assert_eq!(stats.events["Marriage"], 1);
+    assert_eq!(stats.children_per_family.get(&1), Some(&1));
+    assert_eq!(stats.average_children(), Some(0.5));
+    assert_eq!(stats.top_surnames, vec![("Doe".to_string(), 2)]);
+    assert_eq!(stats.top_places, vec![("Boston".to_string(), 3)]);
+}
+
+#[test]
+fn counts_names_by_part_and_phonetic_code() {
+    assert_eq!(daitch_mokotoff("Moskowitz"), vec!["645740"]);
+    assert_eq!(daitch_mokotoff("Auerbach"), vec!["097500", "097400"]);
+    assert_eq!(daitch_mokotoff("Jackson"), vec!["154600", "145460", "454600", "445460"]);
+    assert_eq!(daitch_mokotoff("Peters"), vec!["739400", "734000"]);
+    assert_eq!(phonetic_keys("Schmidt"), phonetic_keys("Shmidt"));
+    assert_eq!(phonetic_keys("Moskowitz"), phonetic_keys("Moskovitz"));
+    assert!(phonetic_keys("123").is_empty());
+
+    let data = GedcomData::builder()
+        .individual("a").name("John Paul /Smith/").birth("1850", "Boston")
+        .individual("b").name("John /Smyth/").birth("ABT 1910", "Salem")
+        .individual("c").name("Mary /Smith/").birth("1899", "Boston")
+        .individual("d").name("Jon /Jones/")
+        .build()
+        .unwrap();
+    let analyzer = Analyzer::new(&data).unwrap();
+    let names = analyzer.name_frequencies();
+
+    let smith = &names.surnames[0];
+    assert_eq!((smith.name.as_str(), smith.count), ("Smith", 2));
+    assert_eq!(smith.centuries.iter().collect::<Vec<_>>(), vec![(&1800, &2)]);
+    assert_eq!(smith.places["Boston"], 2);
+    let given: Vec<_> = names.given_names.iter().map(|name| (name.name.as_str(), name.count)).collect();
+    assert_eq!(given, vec![("John", 2), ("Jon", 1), ("Mary", 1), ("Paul", 1)]);
+
+    let groups = names.surname_groups(Encoding::Soundex);
+    assert_eq!((groups[0].code.as_str(), groups[0].count), ("S530", 3));
+    assert_eq!(groups[0].names, vec!["Smith", "Smyth"]);
+    let groups = names.given_name_groups(Encoding::DaitchMokotoff);
+    assert_eq!(groups[0].names, vec!["John", "Jon"]);
+    assert_eq!(groups[0].count, 3);
+    let groups = names.surname_groups(Encoding::PhoneticKeys);
+    assert_eq!(groups[0].names, vec!["Smith", "Smyth"]);
+
+    let json: serde_json::Value = serde_json::from_str(&names.to_json().unwrap()).unwrap();
+    assert_eq!(json["surnames"][0]["places"]["Boston"], 2);
+}